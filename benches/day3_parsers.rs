@@ -0,0 +1,48 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use aoc_2024::day3_mull_it_over::{
+    ParseMode, read_input_file, get_sum_of_mults, get_sum_of_mults_regex,
+    get_sum_of_mults_with_instructions, get_sum_of_mults_with_instructions_regex, get_sum_of_mults_scanner,
+};
+use aoc_2024::Result;
+
+
+/// A parser that is benchmarked together with the sum of its muls
+type Parser = fn(&str) -> Result<u64>;
+
+/// How many times the puzzle input is repeated
+const INPUT_REPEAT: usize = 100;
+/// Number of 'mul(1,1)' followed by a single "don't()", quadratic for the hand-written part 2
+const WORST_CASE_MULS: usize = 20_000;
+
+const PARSERS: [(&str, Parser); 6] = [
+    ("part 1/hand-written", |input| get_sum_of_mults(input, ParseMode::Strict)),
+    ("part 1/regex", |input| get_sum_of_mults_regex(input, ParseMode::Strict)),
+    ("part 1/scanner", |input| get_sum_of_mults_scanner(input, ParseMode::Strict, false)),
+    ("part 2/hand-written", |input| get_sum_of_mults_with_instructions(input, ParseMode::Strict)),
+    ("part 2/regex", |input| get_sum_of_mults_with_instructions_regex(input, ParseMode::Strict)),
+    ("part 2/scanner", |input| get_sum_of_mults_scanner(input, ParseMode::Strict, true)),
+];
+
+/// Times the parsers of both parts on the enlarged puzzle input and on a worst case input
+fn bench_parsers(c: &mut Criterion) {
+    let puzzle_input = read_input_file().expect("the day 3 input is needed for the benchmark");
+    let inputs = [
+        (format!("input x{INPUT_REPEAT}"), puzzle_input.repeat(INPUT_REPEAT)),
+        ("worst case".to_string(), format!("{}don't()", "mul(1,1)".repeat(WORST_CASE_MULS))),
+    ];
+
+    for (name_input, input) in &inputs {
+        let mut group = c.benchmark_group(format!("day3 {name_input}"));
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.sample_size(10);
+        for (name_parser, parser) in PARSERS {
+            group.bench_with_input(BenchmarkId::from_parameter(name_parser), input.as_str(), |b, input| {
+                b.iter(|| parser(input))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_parsers);
+criterion_main!(benches);
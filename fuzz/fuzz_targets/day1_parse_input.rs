@@ -0,0 +1,10 @@
+#![no_main]
+
+use aoc_2024::day1_historian_hysteria::parse_input;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(lists) = parse_input(input) {
+        assert_eq!(lists.left.len(), lists.right.len());
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use aoc_2024::day2_red_nosed_reports::parse_reports;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(reports) = parse_reports(input) {
+        assert_eq!(reports.len(), input.lines().count());
+    }
+});
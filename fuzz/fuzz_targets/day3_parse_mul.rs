@@ -0,0 +1,19 @@
+#![no_main]
+
+use aoc_2024::day3_mull_it_over::{MultInstruction, ParseMode};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for mode in [ParseMode::Strict, ParseMode::Lenient] {
+        if let Some((_, len)) = MultInstruction::parse(input, mode) {
+            assert!(input.is_char_boundary(len));
+        }
+
+        let muls = MultInstruction::parse_muls(input, mode);
+        assert!(muls.iter().all(|mul| input[mul.offset..].starts_with(MultInstruction::MUL)));
+        let _ = MultInstruction::sum_muls(&muls);
+
+        let enabled = MultInstruction::parse_muls_with_instruction(input, mode);
+        assert!(enabled.len() <= muls.len());
+    }
+});
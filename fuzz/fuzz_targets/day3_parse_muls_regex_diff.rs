@@ -0,0 +1,17 @@
+#![no_main]
+
+use aoc_2024::day3_mull_it_over::{MultInstruction, ParseMode};
+use libfuzzer_sys::fuzz_target;
+
+// Only the strict grammar is compared, in lenient mode the hand-written parser
+// also accepts what `str::parse::<u32>` accepts, e.g. "mul(+1,2)"
+fuzz_target!(|input: &str| {
+    assert_eq!(
+        MultInstruction::parse_muls(input, ParseMode::Strict),
+        MultInstruction::parse_muls_regex(input, ParseMode::Strict),
+    );
+    assert_eq!(
+        MultInstruction::parse_muls_with_instruction(input, ParseMode::Strict),
+        MultInstruction::parse_muls_with_instruction_regex(input, ParseMode::Strict),
+    );
+});
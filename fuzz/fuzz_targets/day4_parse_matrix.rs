@@ -0,0 +1,13 @@
+#![no_main]
+
+use aoc_2024::day4_ceres_search::Matrix;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for fold_case in [false, true] {
+        if let Ok(matrix) = Matrix::parse_with(input, fold_case) {
+            let _ = matrix.count_word("XMAS");
+            let _ = matrix.find_cross();
+        }
+    }
+});
@@ -0,0 +1,119 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+use aoc_2024::day4_ceres_search::{Colouring, MatchFormat};
+use aoc_2024::grid::Window;
+use aoc_2024::synthetic::SyntheticSpec;
+use crate::{Error, Result};
+
+
+/// Options given to the runner on the command line, e.g.
+///
+/// cargo run --release -- --lenient
+#[derive(Debug, Default)]
+pub(super) struct Options {
+    /// Day 3: accept any u32 operands in 'mul(X,Y)' instead of the strict 1-3 digits
+    pub lenient: bool,
+    /// Day 3: print every recognised instruction of part 2 with the machine state
+    pub trace: bool,
+    /// Day 3: print the recognised instructions without the corrupted noise
+    pub disassemble: bool,
+    /// Day 4: search only this window of the input
+    pub window: Option<Window>,
+    /// Day 4: words and crosses wrap around the edges of the grid
+    pub torus: bool,
+    /// Day 4: number of threads that search bands of rows in parallel, 0 and 1 search sequentially
+    pub threads: usize,
+    /// Day 4: dump the positions and directions of the matches in this format
+    pub matches: Option<MatchFormat>,
+    /// Day 4: print the grid with the matches highlighted in colours per direction or per match
+    pub render: Option<Colouring>,
+    /// Day 4: report how often and where each of these comma separated words occurs
+    pub words: Option<Vec<String>>,
+    /// Day 4: report the paths that spell each of these comma separated words, turning at any letter
+    pub paths: Option<Vec<String>>,
+    /// Day 4: longest path that is searched for `paths`
+    pub max_path_len: Option<usize>,
+    /// Day 4: count "XMAS" in this file of 2D layers separated by blank lines, e.g. a 3D cube
+    pub layers: Option<PathBuf>,
+    /// Day 4: compare the letters of the words ignoring their case
+    pub ignore_case: bool,
+    /// Generate a puzzle input instead of solving the puzzles
+    pub gen: Option<GenOptions>,
+}
+
+/// Options of the 'gen' command that generates a puzzle input, e.g.
+///
+/// cargo run --release -- gen --day 3 --size 1000 --seed 7 --expected
+#[derive(Debug, Default)]
+pub(super) struct GenOptions {
+    pub spec: SyntheticSpec,
+    /// Write the input to this file instead of printing it
+    pub output: Option<PathBuf>,
+    /// Also print the answers of a reference solver, or write them next to the output
+    pub expected: bool,
+}
+
+impl Options {
+    pub fn from_args() -> Result<Self> {
+        let mut options = Self::default();
+        let mut args = std::env::args().skip(1).peekable();
+        if args.next_if_eq("gen").is_some() {
+            options.gen = Some(GenOptions::from_args(args)?);
+            return Ok(options);
+        }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--lenient" => options.lenient = true,
+                "--trace" => options.trace = true,
+                "--disassemble" => options.disassemble = true,
+                "--ignore-case" => options.ignore_case = true,
+                "--torus" => options.torus = true,
+                "--parallel" => options.threads = std::thread::available_parallelism().map_or(1, usize::from),
+                "--threads" => options.threads = Self::number_of(&arg, args.next())?,
+                "--window" => options.window = Some(Self::value_of(&arg, args.next())?.parse()?),
+                "--matches" => options.matches = Some(Self::value_of(&arg, args.next())?.parse()?),
+                "--render" => options.render = Some(Self::value_of(&arg, args.next())?.parse()?),
+                "--words" => options.words = Some(
+                    Self::value_of(&arg, args.next())?.split(',').map(str::to_string).collect()
+                ),
+                "--paths" => options.paths = Some(
+                    Self::value_of(&arg, args.next())?.split(',').map(str::to_string).collect()
+                ),
+                "--max-path-len" => options.max_path_len = Some(Self::number_of(&arg, args.next())?),
+                "--layers" => options.layers = Some(PathBuf::from(Self::value_of(&arg, args.next())?)),
+                _ => return Err(Error::InvalidArgument { arg }),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Value that follows an option, e.g. 'json' in '--matches json'
+    fn value_of(arg: &str, value: Option<String>) -> Result<String> {
+        value.ok_or_else(|| Error::InvalidArgument { arg: format!("{arg} needs a value") })
+    }
+
+    /// Number that follows an option, e.g. '4' in '--threads 4'
+    fn number_of<T: FromStr<Err: Display>>(arg: &str, value: Option<String>) -> Result<T> {
+        Self::value_of(arg, value)?.parse()
+            .map_err(|e| Error::InvalidArgument { arg: format!("{arg}: {e}") })
+    }
+}
+
+impl GenOptions {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--day" => options.spec.day = Options::number_of(&arg, args.next())?,
+                "--size" => options.spec.size = Options::number_of(&arg, args.next())?,
+                "--seed" => options.spec.seed = Options::number_of(&arg, args.next())?,
+                "--density" => options.spec.density = Some(Options::number_of(&arg, args.next())?),
+                "--output" => options.output = Some(PathBuf::from(Options::value_of(&arg, args.next())?)),
+                "--expected" => options.expected = true,
+                _ => return Err(Error::InvalidArgument { arg }),
+            }
+        }
+        Ok(options)
+    }
+}
@@ -2,19 +2,81 @@ use crate::Result;
 use super::{LocationLists, read_input};
 
 
-pub fn total_distance() -> Result<i32>{
-    let mut location_lists: LocationLists = read_input()?;
+pub fn total_distance() -> Result<u64>{
+    let location_lists: LocationLists = read_input()?;
+    Ok(sum_of_distances(location_lists))
+}
 
+/// Pairs up the smallest remaining IDs of both lists and sums their distances,
+/// which is the minimal sum over all the ways to pair them up. The sum is kept
+/// in u64 since the distances of long lists overflow i32.
+pub(super) fn sum_of_distances(mut location_lists: LocationLists) -> u64 {
     // Sort left and right lists separately
     location_lists.left.sort();
     location_lists.right.sort();
 
     // Sum all the distances in the sorted lists
-    let mut sum: i32 = 0;
+    let mut sum: u64 = 0;
     for (left, right) in location_lists {
-        let distance = (left - right).abs();
+        let distance = u64::from(left.abs_diff(right));
         sum += distance;
     }
 
-    Ok(sum)
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Brute-force reference that tries every pairing of the right list with the left one
+    fn min_sum_of_distances_reference(left: &[i32], right: &mut Vec<i32>) -> u64 {
+        let Some((&first, left)) = left.split_first() else {
+            return 0;
+        };
+        (0..right.len())
+            .map(|idx| {
+                let paired = right.remove(idx);
+                let sum = u64::from(first.abs_diff(paired)) + min_sum_of_distances_reference(left, right);
+                right.insert(idx, paired);
+                sum
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Two lists of the same length with IDs that keep the sums far from overflowing
+    fn location_lists() -> impl Strategy<Value = (Vec<i32>, Vec<i32>)> {
+        (0..7usize).prop_flat_map(|len| {
+            let ids = || prop::collection::vec(-1000..1000i32, len);
+            (ids(), ids())
+        })
+    }
+
+    #[test]
+    fn should_sum_distances_of_example() {
+        let left = vec![3, 4, 2, 1, 3, 3];
+        let right = vec![4, 3, 5, 3, 9, 3];
+        assert_eq!(sum_of_distances(LocationLists { left, right }), 11);
+    }
+
+    #[test]
+    fn should_sum_distances_beyond_i32() {
+        let len = 30_000;
+        let left = vec![10_000; len];
+        let right = vec![99_999; len];
+        assert_eq!(sum_of_distances(LocationLists { left, right }), 89_999 * len as u64);
+
+        let extremes = LocationLists { left: vec![i32::MIN, i32::MIN], right: vec![i32::MAX, i32::MAX] };
+        assert_eq!(sum_of_distances(extremes), 2 * u64::from(u32::MAX));
+    }
+
+    proptest! {
+        #[test]
+        fn should_sum_distances_like_minimal_matching((left, right) in location_lists()) {
+            let expected = min_sum_of_distances_reference(&left, &mut right.clone());
+            prop_assert_eq!(sum_of_distances(LocationLists { left, right }), expected);
+        }
+    }
 }
@@ -0,0 +1,51 @@
+use rand::{RngExt, SeedableRng};
+use rand_xorshift::XorShiftRng;
+use crate::synthetic::SyntheticInput;
+
+
+/// Range of the five digit location IDs like in the puzzle input
+const IDS: std::ops::Range<i32> = 10_000..100_000;
+
+/// Generates the lists with `size` lines of random location IDs. The answer pairs
+/// the i-th smallest IDs of both lists by walking their counts per ID instead of sorting.
+pub fn generate_location_lists(size: usize, seed: u64) -> SyntheticInput {
+    let mut rng = XorShiftRng::seed_from_u64(seed);
+    let mut counts_left = vec![0; IDS.len()];
+    let mut counts_right = vec![0; IDS.len()];
+    let mut input = String::new();
+    for _ in 0..size {
+        let pair = (rng.random_range(IDS), rng.random_range(IDS));
+        input.push_str(&format!("{}   {}\n", pair.0, pair.1));
+        counts_left[(pair.0 - IDS.start) as usize] += 1;
+        counts_right[(pair.1 - IDS.start) as usize] += 1;
+    }
+
+    let distance = ids_in_order(&counts_left)
+        .zip(ids_in_order(&counts_right))
+        .map(|(left, right)| u64::from(left.abs_diff(right)))
+        .sum();
+    SyntheticInput { input, answers: vec![distance] }
+}
+
+/// Every ID as often as it was counted, from the smallest to the largest
+fn ids_in_order(counts: &[usize]) -> impl Iterator<Item = i32> + '_ {
+    IDS.zip(counts).flat_map(|(id, &count)| std::iter::repeat_n(id, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day1_historian_hysteria::parse_input;
+    use crate::day1_historian_hysteria::distance_calculator::sum_of_distances;
+
+    #[test]
+    fn should_generate_lists_with_answer() {
+        for seed in 0..10 {
+            let generated = generate_location_lists(50, seed);
+            let lists = parse_input(&generated.input).unwrap();
+            assert_eq!(lists.left.len(), 50);
+            assert_eq!(generated.answers, vec![sum_of_distances(lists)]);
+        }
+        assert_eq!(generate_location_lists(0, 0).answers, vec![0]);
+    }
+}
@@ -7,16 +7,21 @@ pub(super) fn read_input() -> Result<LocationLists> {
     let lists = std::fs::read_to_string(FILENAME)
         .map_err(|e| Error::CannotReadFile { msg: e.to_string() } )?;
 
+    parse_input(&lists)
+}
+
+/// Parses lines of two location IDs separated by three spaces
+pub fn parse_input(lists: &str) -> Result<LocationLists> {
+
     // Read line by line into these lists
     let mut left = Vec::<i32>::new();
     let mut right = Vec::<i32>::new();
 
     for line in lists.lines() {
         let pair: Vec<&str> = line.split("   ").collect();
-        assert!(pair.len() == 2);
-
-        let first_element = pair[0];
-        let second_element = pair[1];
+        let [first_element, second_element] = pair[..] else {
+            return Err(Error::CannotParseLine);
+        };
 
         let first_element: i32 = first_element.parse()
             .map_err(|_| Error::CannotParseLine)?;
@@ -31,3 +36,22 @@ pub(super) fn read_input() -> Result<LocationLists> {
     let both_lists_together = LocationLists { left, right };
     Ok(both_lists_together)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_pairs() {
+        let lists = parse_input("3   4\n4   3\n-2   5").unwrap();
+        assert_eq!(lists.left, vec![3, 4, -2]);
+        assert_eq!(lists.right, vec![4, 3, 5]);
+    }
+
+    #[test]
+    fn should_reject_malformed_lines() {
+        for input in ["3   4   5", "3 4", "3   ", "3   x", "   "] {
+            assert!(matches!(parse_input(input), Err(Error::CannotParseLine)), "{input:?}");
+        }
+    }
+}
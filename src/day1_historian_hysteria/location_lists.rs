@@ -1,5 +1,6 @@
 
-pub(super) struct LocationLists {
+/// Location IDs of the left and the right list, pairs share the same index
+pub struct LocationLists {
     pub left: Vec<i32>,
     pub right: Vec<i32>,
 }
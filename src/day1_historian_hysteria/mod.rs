@@ -1,9 +1,12 @@
 mod input_file_reader;
 mod location_lists;
+mod generator;
 pub mod distance_calculator;
 
 use input_file_reader::read_input;
-use location_lists::LocationLists;
+pub use input_file_reader::parse_input;
+pub use location_lists::LocationLists;
 pub use distance_calculator::total_distance;
+pub use generator::generate_location_lists;
 
 const FILENAME: &str = "data/day1_input.txt";
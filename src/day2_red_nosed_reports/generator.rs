@@ -0,0 +1,65 @@
+use rand::{RngExt, SeedableRng};
+use rand_xorshift::XorShiftRng;
+use super::{is_report_safe_reference, is_report_safe_dampened_reference};
+use crate::synthetic::SyntheticInput;
+
+
+/// Generates `size` reports of 5-8 levels. A third of them is safe, a third has one
+/// bad level that the dampener may tolerate and a third has random levels.
+pub fn generate_reports(size: usize, seed: u64) -> SyntheticInput {
+    let mut rng = XorShiftRng::seed_from_u64(seed);
+    let mut input = String::new();
+    let mut num_safe: u64 = 0;
+    let mut num_safe_dampened: u64 = 0;
+    for _ in 0..size {
+        let len = rng.random_range(5..=8);
+        let report: Vec<i32> = match rng.random_range(0..3) {
+            0 | 1 => {
+                // Starts far enough from 0 and 100 to stay within them
+                let sign = if rng.random_bool(0.5) { 1 } else { -1 };
+                let mut level: i32 = rng.random_range(30..70);
+                let mut report: Vec<i32> = (0..len)
+                    .map(|_| {
+                        level += sign * rng.random_range(1..=3);
+                        level
+                    })
+                    .collect();
+                if report.len() > 1 && rng.random_bool(0.5) {
+                    let idx = rng.random_range(0..report.len());
+                    report[idx] = rng.random_range(1..100);
+                }
+                report
+            },
+            _ => (0..len).map(|_| rng.random_range(1..100)).collect(),
+        };
+
+        num_safe += u64::from(is_report_safe_reference(&report));
+        num_safe_dampened += u64::from(is_report_safe_dampened_reference(&report));
+        let levels: Vec<String> = report.iter().map(i32::to_string).collect();
+        input.push_str(&levels.join(" "));
+        input.push('\n');
+    }
+
+    SyntheticInput { input, answers: vec![num_safe, num_safe_dampened] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day2_red_nosed_reports::parse_reports;
+    use crate::day2_red_nosed_reports::report_validator::{is_report_safe, is_report_safe_dampened};
+
+    #[test]
+    fn should_generate_reports_with_answers() {
+        for seed in 0..10 {
+            let generated = generate_reports(100, seed);
+            let reports = parse_reports(&generated.input).unwrap();
+            assert_eq!(reports.len(), 100);
+            let num_safe = reports.iter().filter(|report| is_report_safe(report)).count() as u64;
+            let num_safe_dampened = reports.iter().filter(|report| is_report_safe_dampened(report)).count() as u64;
+            assert_eq!(generated.answers, vec![num_safe, num_safe_dampened]);
+            // Every kind of report occurs
+            assert!(0 < num_safe && num_safe < num_safe_dampened && num_safe_dampened < 100);
+        }
+    }
+}
@@ -6,6 +6,12 @@ pub(super) fn read_input_file() -> Result<ReportList> {
     let input_str = std::fs::read_to_string(FILENAME)
         .map_err(|e| Error::CannotReadFile { msg: e.to_string() })?;
 
+    parse_reports(&input_str)
+}
+
+/// Parses one report per line with its levels separated by a space
+pub fn parse_reports(input_str: &str) -> Result<ReportList> {
+
     let mut reports: ReportList = Vec::new();
     for line in input_str.lines() {
         let report: Vec<i32> = line
@@ -1,9 +1,14 @@
 mod report_list;
 mod input_file_reader;
 mod report_validator;
+mod reference;
+mod generator;
 
-use report_list::ReportList;
+pub use report_list::ReportList;
 use input_file_reader::read_input_file;
+use reference::{is_report_safe_reference, is_report_safe_dampened_reference};
+pub use input_file_reader::parse_reports;
 pub use report_validator::get_safe_reports_len;
+pub use generator::generate_reports;
 
 const FILENAME: &str = "data/day2_input.txt";
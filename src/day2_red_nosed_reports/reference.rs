@@ -0,0 +1,18 @@
+//! Brute-force report checks, used for the generated answers and to test the validator
+
+
+/// Checks both rules on all the steps at once
+pub(super) fn is_report_safe_reference(report: &[i32]) -> bool {
+    let steps: Vec<i32> = report.windows(2).map(|step| step[1] - step[0]).collect();
+    !steps.is_empty()
+        && (steps.iter().all(|step| (1..=3).contains(step)) || steps.iter().all(|step| (-3..=-1).contains(step)))
+}
+
+/// Removes every level once and checks what is left with the reference rules
+pub(super) fn is_report_safe_dampened_reference(report: &[i32]) -> bool {
+    is_report_safe_reference(report)
+        || (0..report.len()).any(|idx_removed| {
+            let subset: Vec<i32> = [&report[..idx_removed], &report[(idx_removed + 1)..]].concat();
+            is_report_safe_reference(&subset)
+        })
+}
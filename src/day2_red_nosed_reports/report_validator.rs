@@ -17,27 +17,17 @@ pub fn get_safe_reports_len(tolerate: bool) -> Result<u64> {
     // Then the report is safe.
 
     for report in &reports {
-        if is_report_safe(report) {
+        // Only tolerate a bad level if we are in the second part
+        let is_safe = if tolerate { is_report_safe_dampened(report) } else { is_report_safe(report) };
+        if is_safe {
             num_safe_reports += 1;
-        } else {
-            // Only check again if we are in the second part (tolerate)
-            if tolerate {
-                // Test report safety in all subsets of size N and N-1
-                let subsets: Vec<Vec<i32>> = subsets_of_size_minus_one(report);
-                for subset in subsets {
-                    if is_report_safe(&subset) {
-                        num_safe_reports += 1;
-                        break // Found one safe subset
-                    }
-                }
-            }
         }
     }
 
     Ok(num_safe_reports)
 }
 
-fn is_report_safe(report: &[i32]) -> bool {
+pub(super) fn is_report_safe(report: &[i32]) -> bool {
     // Trivial case
     if report.len() <= 1 {
         return false;
@@ -81,7 +71,13 @@ fn is_report_safe(report: &[i32]) -> bool {
     true
 }
 
-/// Returns all subsets of lenght N-1 given array length equals N
+/// Whether the report is safe with at most one level removed
+pub(super) fn is_report_safe_dampened(report: &[i32]) -> bool {
+    // Test report safety in all subsets of size N and N-1
+    is_report_safe(report) || subsets_of_size_minus_one(report).iter().any(|subset| is_report_safe(subset))
+}
+
+/// Returns all subsets of length N-1 given array length equals N
 fn subsets_of_size_minus_one(lst: &[i32]) -> Vec<Vec<i32>> {
     let mut subsets = Vec::new();
 
@@ -99,3 +95,38 @@ fn subsets_of_size_minus_one(lst: &[i32]) -> Vec<Vec<i32>> {
 
     subsets
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day2_red_nosed_reports::is_report_safe_reference;
+    use proptest::prelude::*;
+
+    /// Short reports whose levels are close enough to be safe every now and then
+    fn report() -> impl Strategy<Value = Vec<i32>> {
+        prop::collection::vec(0..8i32, 0..8)
+    }
+
+    #[test]
+    fn should_check_example_reports() {
+        let reports = [
+            (vec![7, 6, 4, 2, 1], true, true),
+            (vec![1, 2, 7, 8, 9], false, false),
+            (vec![9, 7, 6, 2, 1], false, false),
+            (vec![1, 3, 2, 4, 5], false, true),
+            (vec![8, 6, 4, 4, 1], false, true),
+            (vec![1, 3, 6, 7, 9], true, true),
+        ];
+        for (report, is_safe, is_safe_dampened) in reports {
+            assert_eq!(is_report_safe(&report), is_safe, "{report:?}");
+            assert_eq!(is_report_safe_dampened(&report), is_safe_dampened, "{report:?}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn should_check_safety_like_reference(report in report()) {
+            prop_assert_eq!(is_report_safe(&report), is_report_safe_reference(&report));
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use rand::seq::IndexedRandom;
+use rand::{RngExt, SeedableRng};
+use rand_xorshift::XorShiftRng;
+use super::{parse_muls_reference, MultInstruction};
+use crate::synthetic::SyntheticInput;
+
+
+/// Corrupted pieces between the instructions, some of them are close to being instructions
+const NOISE: [&str; 24] = [
+    "mul[3,7]", "mul(32,64]", "mul ( 2 , 4 )", "mul(1234,5)", "mul(4*", "mul(", "don't", "do(", "do_not_",
+    "select()", "what()", "from()", "how()", "where()", "'", "!", "@", "#", "%", "^", "&", "+", " ", "\n",
+];
+
+/// Probability of a 'do()' or "don't()" before a mul if no density is given
+pub const DEFAULT_TOGGLE_DENSITY: f64 = 0.1;
+
+/// Generates corrupted memory with `size` valid muls. Each of them is preceded by 'do()'
+/// or "don't()" with the probability `toggle_density` and by a few corrupted pieces.
+/// The answers are the sums of a naive parser.
+pub fn generate_memory(size: usize, toggle_density: f64, seed: u64) -> SyntheticInput {
+    let mut rng = XorShiftRng::seed_from_u64(seed);
+    let mut input = String::new();
+    for _ in 0..size {
+        for _ in 0..rng.random_range(0..8) {
+            input.push_str(NOISE.choose(&mut rng).expect("noise is not empty"));
+        }
+        if rng.random_bool(toggle_density) {
+            input.push_str(if rng.random_bool(0.5) { MultInstruction::ENABLE } else { MultInstruction::DISABLE });
+        }
+        input.push_str(&format!("mul({},{})", rng.random_range(1..1000), rng.random_range(1..1000)));
+    }
+
+    let sum = |muls: Vec<MultInstruction>| muls.iter().map(|mul| u64::from(mul.lhs) * u64::from(mul.rhs)).sum();
+    let answers = vec![sum(parse_muls_reference(&input, false)), sum(parse_muls_reference(&input, true))];
+    SyntheticInput { input, answers }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day3_mull_it_over::ParseMode;
+
+    fn parse_and_sum(input: &str, with_instructions: bool) -> u64 {
+        let muls = if with_instructions {
+            MultInstruction::parse_muls_with_instruction(input, ParseMode::Strict)
+        } else {
+            MultInstruction::parse_muls(input, ParseMode::Strict)
+        };
+        MultInstruction::sum_muls(&muls).unwrap()
+    }
+
+    #[test]
+    fn should_generate_memory_with_answers() {
+        for seed in 0..10 {
+            let generated = generate_memory(100, 0.2, seed);
+            assert_eq!(generated.answers, vec![parse_and_sum(&generated.input, false), parse_and_sum(&generated.input, true)]);
+            assert!(generated.answers[1] < generated.answers[0]);
+        }
+    }
+
+    #[test]
+    fn should_control_toggle_density() {
+        let generated = generate_memory(100, 0.0, 1);
+        assert!(!generated.input.contains(MultInstruction::ENABLE));
+        assert_eq!(generated.answers[0], generated.answers[1]);
+
+        let generated = generate_memory(100, 1.0, 1);
+        assert_eq!(generated.input.matches(MultInstruction::ENABLE).count() + generated.input.matches(MultInstruction::DISABLE).count(), 100);
+    }
+}
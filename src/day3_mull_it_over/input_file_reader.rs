@@ -3,7 +3,13 @@ use super::FILENAME;
 
 /// For this task we just read the file into string
 /// and return it to be processed by the parser.
-pub(super) fn read_input_file() -> Result<String> {
+pub fn read_input_file() -> Result<String> {
     std::fs::read_to_string(FILENAME)
         .map_err(|e| Error::CannotReadFile { msg: e.to_string() } )
 }
+
+/// Opens the file without reading it, for the parsers that stream it chunk by chunk.
+pub fn open_input_file() -> Result<std::fs::File> {
+    std::fs::File::open(FILENAME)
+        .map_err(|e| Error::CannotReadFile { msg: e.to_string() } )
+}
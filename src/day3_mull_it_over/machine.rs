@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+use super::{MultInstruction, ParseMode};
+use crate::{Error, Result};
+
+
+/// State of the machine that changes while instructions are executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachineState {
+    /// Whether opcodes are applied, toggled by 'do()' and "don't()"
+    pub enabled: bool,
+    /// Result of the applied opcodes so far
+    pub accumulator: u64,
+}
+
+impl Default for MachineState {
+    fn default() -> Self {
+        Self { enabled: true, accumulator: 0 }
+    }
+}
+
+/// Applies an opcode with its evaluated arguments to the machine state and returns its result,
+/// e.g. the product of 'mul'. Returns `None` if the arithmetic overflows.
+pub type OpcodeHandler = fn(&mut MachineState, &[u64]) -> Option<u64>;
+
+/// An opcode that can be registered to the machine, e.g. 'mul' with arity 2
+struct Opcode {
+    /// Number of arguments between the parentheses
+    arity: usize,
+    handler: OpcodeHandler,
+}
+
+/// Argument of a call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Argument {
+    /// 1-3 ASCII digits
+    Literal(u32),
+    /// name(arg1,arg2,...) of a registered opcode, only parsed with [`Machine::with_nesting`]
+    Call {
+        name: &'static str,
+        args: Vec<Argument>,
+    },
+}
+
+/// A single instruction recognised in the corrupted memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    /// do()
+    Enable,
+    /// don't()
+    Disable,
+    /// name(arg1,arg2,...) of a registered opcode
+    Call {
+        name: &'static str,
+        args: Vec<Argument>,
+    },
+}
+
+/// Writes "name(arg1,arg2,...)"
+fn fmt_call(f: &mut std::fmt::Formatter<'_>, name: &str, args: &[Argument]) -> std::fmt::Result {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    write!(f, "{}({})", name, args.join(","))
+}
+
+impl std::fmt::Display for Argument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Argument::Literal(value) => write!(f, "{value}"),
+            Argument::Call { name, args } => fmt_call(f, name, args),
+        }
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Enable => write!(f, "{}", MultInstruction::ENABLE),
+            Instruction::Disable => write!(f, "{}", MultInstruction::DISABLE),
+            Instruction::Call { name, args } => fmt_call(f, name, args),
+        }
+    }
+}
+
+/// Small virtual machine for the corrupted memory language.
+///
+/// Only 'mul' is known by default, other opcodes can be added with
+/// [`Machine::register`] so puzzle variants are evaluated by the same parser.
+pub struct Machine {
+    state: MachineState,
+    opcodes: HashMap<&'static str, Opcode>,
+    /// How deep calls may be nested as arguments, 0 if the arguments are literals only
+    max_depth: usize,
+    /// How the literal operands are parsed
+    mode: ParseMode,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Machine {
+    /// Deeper nested calls are treated as noise, so the recursion is bounded
+    pub const MAX_DEPTH: usize = 32;
+
+    pub fn new() -> Self {
+        let mut machine = Self { state: MachineState::default(), opcodes: HashMap::new(), max_depth: 0, mode: ParseMode::Strict };
+        machine.register(MultInstruction::MUL, 2, |state, args| {
+            let product = args[0].checked_mul(args[1])?;
+            state.accumulator = state.accumulator.checked_add(product)?;
+            Some(product)
+        });
+        machine
+    }
+
+    /// Allows calls as arguments up to [`Machine::MAX_DEPTH`] levels deep, e.g. 'mul(mul(2,3),4)'.
+    /// A nested call only passes on its result, its handler is applied to a fresh state.
+    pub fn with_nesting(mut self) -> Self {
+        self.max_depth = Self::MAX_DEPTH;
+        self
+    }
+
+    /// Parses the literal operands like the mul parsers do in the given mode
+    pub fn with_parse_mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Registers a new opcode, replacing a previous one with the same name.
+    /// The handler is only called while the machine is enabled.
+    pub fn register(&mut self, name: &'static str, arity: usize, handler: OpcodeHandler) {
+        self.opcodes.insert(name, Opcode { arity, handler });
+    }
+
+    pub fn state(&self) -> &MachineState {
+        &self.state
+    }
+
+    /// Resets the state so a new program can be executed
+    pub fn reset(&mut self) {
+        self.state = MachineState::default();
+    }
+
+    /// Parses all the instructions from the corrupted memory together with
+    /// their byte offsets, anything that is not a valid instruction is skipped as noise.
+    pub fn parse(&self, memory: &str) -> Vec<(usize, Instruction)> {
+        let mut instructions = Vec::new();
+        let bytes = memory.as_bytes();
+
+        let mut idx: usize = 0;
+        while idx < bytes.len() {
+            match self.parse_instruction(&bytes[idx..]) {
+                Some((instruction, len)) => {
+                    instructions.push((idx, instruction));
+                    idx += len;
+                },
+                None => idx += 1,
+            }
+        }
+
+        instructions
+    }
+
+    /// Tries to parse a single instruction at the start of the given bytes
+    /// and returns it together with its byte length.
+    fn parse_instruction(&self, bytes: &[u8]) -> Option<(Instruction, usize)> {
+        if bytes.starts_with(MultInstruction::ENABLE.as_bytes()) {
+            return Some((Instruction::Enable, MultInstruction::ENABLE.len()));
+        }
+        if bytes.starts_with(MultInstruction::DISABLE.as_bytes()) {
+            return Some((Instruction::Disable, MultInstruction::DISABLE.len()));
+        }
+
+        let ((name, args), len) = self.parse_call(bytes, 0)?;
+        Some((Instruction::Call { name, args }, len))
+    }
+
+    /// Parses "name(arg1,arg2,...)" of a registered opcode with exactly its arity of arguments
+    /// and returns the name and the arguments together with the byte length.
+    fn parse_call(&self, bytes: &[u8], depth: usize) -> Option<((&'static str, Vec<Argument>), usize)> {
+        if depth > self.max_depth {
+            return None;
+        }
+
+        // Prefer the longest opcode name so e.g. 'mull' wins over 'mul'
+        self.opcodes.iter()
+            .filter(|(name, _)| bytes.starts_with(name.as_bytes()))
+            .filter_map(|(name, opcode)| {
+                let (args, len_args) = self.parse_args(&bytes[name.len()..], opcode.arity, depth)?;
+                Some(((*name, args), name.len() + len_args))
+            })
+            .max_by_key(|(_, len)| *len)
+    }
+
+    /// Parses "(arg1,arg2,...)" with exactly `arity` arguments
+    /// and returns the arguments and the byte length of the pattern.
+    fn parse_args(&self, bytes: &[u8], arity: usize, depth: usize) -> Option<(Vec<Argument>, usize)> {
+        if bytes.first() != Some(&b'(') {
+            return None;
+        }
+
+        let mut args = Vec::with_capacity(arity);
+        let mut idx: usize = 1;
+        for idx_arg in 0..arity {
+            if idx_arg > 0 {
+                if bytes.get(idx) != Some(&b',') {
+                    return None;
+                }
+                idx += 1;
+            }
+
+            let (arg, len_arg) = self.parse_argument(&bytes[idx..], depth)?;
+            args.push(arg);
+            idx += len_arg;
+        }
+
+        if bytes.get(idx) != Some(&b')') {
+            return None;
+        }
+
+        Some((args, idx + 1))
+    }
+
+    /// Parses an operand of the parse mode, or a call if nesting is allowed this deep
+    fn parse_argument(&self, bytes: &[u8], depth: usize) -> Option<(Argument, usize)> {
+        let literal = match self.mode {
+            ParseMode::Strict => MultInstruction::parse_operand(bytes),
+            ParseMode::Lenient => MultInstruction::parse_operand_lenient(bytes),
+        };
+        if let Some((operand, len_operand)) = literal {
+            return Some((Argument::Literal(operand), len_operand));
+        }
+        let ((name, args), len) = self.parse_call(bytes, depth + 1)?;
+        Some((Argument::Call { name, args }, len))
+    }
+
+    /// Evaluates the arguments, nested calls on a fresh state. `None` if the arithmetic overflows.
+    fn eval_args(&self, args: &[Argument]) -> Option<Vec<u64>> {
+        args.iter()
+            .map(|arg| match arg {
+                Argument::Literal(value) => Some(u64::from(*value)),
+                Argument::Call { name, args } => {
+                    let opcode = self.opcodes.get(name)?;
+                    (opcode.handler)(&mut MachineState::default(), &self.eval_args(args)?)
+                },
+            })
+            .collect()
+    }
+
+    /// Applies a single instruction found at the given byte offset to the machine state.
+    /// Returns the result of the opcode, `None` if nothing was applied.
+    pub fn execute(&mut self, offset: usize, instruction: &Instruction) -> Result<Option<u64>> {
+        match instruction {
+            Instruction::Enable => self.state.enabled = true,
+            Instruction::Disable => self.state.enabled = false,
+            Instruction::Call { name, args } => {
+                if self.state.enabled {
+                    if let Some(opcode) = self.opcodes.get(name) {
+                        let result = self.eval_args(args)
+                            .and_then(|args| (opcode.handler)(&mut self.state, &args))
+                            .ok_or(Error::ArithmeticOverflow { offset })?;
+                        return Ok(Some(result));
+                    }
+                }
+            },
+        }
+        Ok(None)
+    }
+
+    /// Parses and executes the given corrupted memory from a fresh state
+    /// and returns the accumulator.
+    pub fn run(&mut self, memory: &str) -> Result<u64> {
+        self.reset();
+        for (offset, instruction) in self.parse(memory) {
+            self.execute(offset, &instruction)?;
+        }
+        Ok(self.state.accumulator)
+    }
+}
+
+/// Evaluates the input on the machine with the default opcodes, equivalent to part 2.
+pub fn get_sum_of_mults_with_machine(input: &str) -> Result<u64> {
+    let mut machine = Machine::new();
+    machine.run(input)
+}
+
+/// Evaluates the input on the machine with nested calls and the default opcodes,
+/// equivalent to part 2 as long as there is no nesting.
+pub fn get_sum_of_nested_expressions(input: &str) -> Result<u64> {
+    let mut machine = Machine::new().with_nesting();
+    machine.run(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day3_mull_it_over::get_sum_of_mults_with_instructions;
+
+    /// 'add' and 'sub' add their result to the accumulator, 'reset' clears it
+    fn register_add_sub_reset(machine: &mut Machine) {
+        machine.register("add", 2, |state, args| {
+            let sum = args[0].checked_add(args[1])?;
+            state.accumulator = state.accumulator.checked_add(sum)?;
+            Some(sum)
+        });
+        machine.register("sub", 2, |state, args| {
+            let difference = args[0].checked_sub(args[1])?;
+            state.accumulator = state.accumulator.checked_add(difference)?;
+            Some(difference)
+        });
+        machine.register("reset", 0, |state, _| {
+            state.accumulator = 0;
+            Some(0)
+        });
+    }
+
+    #[test]
+    fn should_run_example_with_instructions() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let mut machine = Machine::new();
+        assert_eq!(machine.run(input).unwrap(), 48);
+        assert!(machine.state.enabled);
+    }
+
+    #[test]
+    fn should_parse_instructions() {
+        let input = "mul(2,3)don't()xmul(4,)do()mul(1,1)";
+        let machine = Machine::new();
+        assert_eq!(machine.parse(input), vec![
+            (0, Instruction::Call { name: "mul", args: vec![Argument::Literal(2), Argument::Literal(3)] }),
+            (8, Instruction::Disable),
+            (23, Instruction::Enable),
+            (27, Instruction::Call { name: "mul", args: vec![Argument::Literal(1), Argument::Literal(1)] }),
+        ]);
+    }
+
+    #[test]
+    fn should_skip_operands_with_more_than_three_digits() {
+        let mut machine = Machine::new();
+        assert_eq!(machine.parse("mul(1234,5)mul(5,1234)mul(0012,3)"), vec![]);
+        assert_eq!(machine.run("mul(1234,5)mul(123,4)").unwrap(), 492);
+    }
+
+    #[test]
+    fn should_parse_lenient_operands() {
+        let input = "mul(1234,5)mul(+2,3)mul(+,3)mul(4294967296,1)don't()mul(0012,3)";
+        let mut machine = Machine::new().with_parse_mode(ParseMode::Lenient);
+        assert_eq!(machine.parse(input).len(), 4);
+        assert_eq!(machine.run(input).unwrap(), 6170 + 6);
+        assert_eq!(machine.run(input).unwrap(), get_sum_of_mults_with_instructions(input, ParseMode::Lenient).unwrap());
+    }
+
+    #[test]
+    fn should_run_registered_opcodes() {
+        let input = "mul(2,3)add(1,4)sub(2,1)don't()add(100,100)do()reset()mul(3,3)";
+        let mut machine = Machine::new();
+        register_add_sub_reset(&mut machine);
+        assert_eq!(machine.run(input).unwrap(), 9);
+
+        // Without the reset: 6 + 5 + 1 + 9
+        let input = "mul(2,3)add(1,4)sub(2,1)don't()add(100,100)reset()do()mul(3,3)";
+        assert_eq!(machine.run(input).unwrap(), 21);
+
+        // 1 - 2 cannot be added to the accumulator
+        assert!(matches!(machine.run("mul(1,1)sub(1,2)"), Err(Error::ArithmeticOverflow { offset: 8 })));
+    }
+
+    #[test]
+    fn should_parse_nested_calls() {
+        let mut machine = Machine::new().with_nesting();
+        register_add_sub_reset(&mut machine);
+        let instructions = machine.parse("x}mul(add(1,2),5)");
+        assert_eq!(instructions, vec![
+            (2, Instruction::Call {
+                name: "mul",
+                args: vec![
+                    Argument::Call { name: "add", args: vec![Argument::Literal(1), Argument::Literal(2)] },
+                    Argument::Literal(5),
+                ],
+            }),
+        ]);
+        assert_eq!(instructions[0].1.to_string(), "mul(add(1,2),5)");
+
+        // Without nesting only the inner call is an instruction
+        let mut machine = Machine::new();
+        register_add_sub_reset(&mut machine);
+        assert_eq!(machine.parse("x}mul(add(1,2),5)"), vec![
+            (6, Instruction::Call { name: "add", args: vec![Argument::Literal(1), Argument::Literal(2)] }),
+        ]);
+    }
+
+    #[test]
+    fn should_run_nested_calls() {
+        let mut machine = Machine::new().with_nesting();
+        register_add_sub_reset(&mut machine);
+        assert_eq!(machine.run("mul(mul(2,3),4)").unwrap(), 24);
+        assert_eq!(machine.run("mul(add(1,2),5)").unwrap(), 15);
+        assert_eq!(machine.run("add(mul(2,mul(3,4)),add(1,1))").unwrap(), 26);
+        assert_eq!(machine.run("don't()mul(mul(2,3),4)do()mul(1,add(2,3))").unwrap(), 5);
+
+        // The nested reset only passes on its result and leaves the accumulator alone
+        assert_eq!(machine.run("mul(2,3)add(reset(),1)").unwrap(), 7);
+    }
+
+    #[test]
+    fn should_skip_malformed_nested_calls() {
+        let mut machine = Machine::new().with_nesting();
+        register_add_sub_reset(&mut machine);
+        // The outer calls are malformed, the inner ones are still valid
+        assert_eq!(machine.run("mul(mul(2,3)").unwrap(), 6);
+        assert_eq!(machine.run("mul(mul(2,3),4]").unwrap(), 6);
+        assert_eq!(machine.run("mul(div(2,3),4)mul(add(1,2,3),4)").unwrap(), 0);
+        assert_eq!(machine.run("mul(mul(1234,1),2)").unwrap(), 0);
+
+        // Too deep nesting is noise as well
+        let input = format!("{}1{}", "add(1,".repeat(40), ")".repeat(40));
+        assert_eq!(machine.run(&input).unwrap(), 33 + 1);
+    }
+
+    #[test]
+    fn should_agree_with_part_2_without_nesting() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(get_sum_of_nested_expressions(input).unwrap(), 48);
+    }
+}
@@ -0,0 +1,78 @@
+use super::{MultInstruction, ParseMode};
+use crate::Result;
+
+
+/// Single pass scanner that finds 'mul(', 'do()' and "don't()" at once.
+///
+/// The parsers in `mult_parser` search for every marker separately, and part 2
+/// searches the rest of the input for "don't()" again after each mul which is
+/// quadratic in the worst case. Here the first bytes of the markers are found
+/// with a SIMD accelerated `memchr2`, so each marker is visited exactly once.
+pub(super) struct MarkerScanner;
+
+impl MarkerScanner {
+    /// 'mul(' is the only marker starting with 'm'
+    const MUL_FIRST: u8 = b'm';
+    /// 'do()' and "don't()" both start with 'd'
+    const INSTRUCTION_FIRST: u8 = b'd';
+
+    /// Parses the muls, with instructions 'do()' and "don't()" are applied as in part 2
+    pub fn parse_muls(input: &str, mode: ParseMode, with_instructions: bool) -> Vec<MultInstruction> {
+        let mut muls = Vec::new();
+        let mut is_enabled = true;
+
+        // The markers cannot occur inside the operands of a valid mul,
+        // so the candidates do not need to be skipped after a parsed mul
+        let bytes = input.as_bytes();
+        for idx in memchr::memchr2_iter(Self::MUL_FIRST, Self::INSTRUCTION_FIRST, bytes) {
+            let rest = &bytes[idx..];
+            if rest.starts_with(MultInstruction::MUL.as_bytes()) {
+                if !is_enabled {
+                    continue;
+                }
+                let idx_operands = idx + MultInstruction::MUL.len();
+                if let Some((mul, _)) = MultInstruction::parse(&input[idx_operands..], mode) {
+                    muls.push(MultInstruction { offset: idx, ..mul });
+                }
+            } else if with_instructions && rest.starts_with(MultInstruction::ENABLE.as_bytes()) {
+                is_enabled = true;
+            } else if with_instructions && rest.starts_with(MultInstruction::DISABLE.as_bytes()) {
+                is_enabled = false;
+            }
+        }
+
+        muls
+    }
+}
+
+/// Finds all the markers of the input in a single pass and reduces the mul results
+/// into a sum. With instructions, 'do()' and "don't()" are applied as in part 2.
+pub fn get_sum_of_mults_scanner(input: &str, mode: ParseMode, with_instructions: bool) -> Result<u64> {
+    let muls = MarkerScanner::parse_muls(input, mode, with_instructions);
+    MultInstruction::sum_muls(&muls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_agree_with_parsers() {
+        let inputs = [
+            "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
+            "mul(123,456)mul(1234,5)mul(+1,2)mulmul(1,2)mul(mul(3,4)mul(1,2",
+            "don't()do()don't()mul(1,2)do(mul(3,4)do()mul(5,6)don'tmul(7,8)",
+            "don't()mul(1,2)",
+            "ümul(1,2)dö()mul(3,4)",
+        ];
+        for input in inputs {
+            for mode in [ParseMode::Strict, ParseMode::Lenient] {
+                assert_eq!(MarkerScanner::parse_muls(input, mode, false), MultInstruction::parse_muls(input, mode));
+                assert_eq!(
+                    MarkerScanner::parse_muls(input, mode, true),
+                    MultInstruction::parse_muls_with_instruction(input, mode),
+                );
+            }
+        }
+    }
+}
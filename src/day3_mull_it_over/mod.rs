@@ -1,7 +1,25 @@
 mod input_file_reader;
 mod mult_parser;
+mod machine;
+mod trace;
+mod stream_parser;
+mod marker_scanner;
+mod reference;
+mod generator;
 
-use input_file_reader::read_input_file;
-pub use mult_parser::{get_sum_of_mults, get_sum_of_mults_regex, get_sum_of_mults_with_instructions};
+pub use input_file_reader::{read_input_file, open_input_file};
+pub use mult_parser::{
+    ParseMode, MultInstruction, get_sum_of_mults, get_sum_of_mults_regex,
+    get_sum_of_mults_with_instructions, get_sum_of_mults_with_instructions_regex,
+};
+use reference::parse_muls_reference;
+pub use machine::{
+    Machine, MachineState, OpcodeHandler, Instruction, Argument,
+    get_sum_of_mults_with_machine, get_sum_of_nested_expressions,
+};
+pub use trace::{get_trace, get_disassembly};
+pub use stream_parser::get_sum_of_mults_streaming;
+pub use marker_scanner::get_sum_of_mults_scanner;
+pub use generator::{generate_memory, DEFAULT_TOGGLE_DENSITY};
 
 const FILENAME: &str = "data/day3_input.txt";
@@ -1,30 +1,104 @@
 use regex::Regex;
-use super::read_input_file;
-use crate::Result;
-
+use crate::{Error, Result};
+
+
+/// How strictly the operands of 'mul(X,Y)' are parsed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// X and Y are 1-3 ASCII digits as in the puzzle grammar
+    #[default]
+    Strict,
+    /// Anything that parses as u32 between the parentheses and the comma,
+    /// i.e. ASCII digits with an optional leading '+'
+    Lenient,
+}
 
 /// mul(lhs,rhs)
-struct MultInstruction {
+#[derive(Debug, PartialEq, Eq)]
+pub struct MultInstruction {
     /// Left hand side
-    lhs: u32,
+    pub lhs: u32,
     /// Right hand side
-    rhs: u32,
+    pub rhs: u32,
+    /// Byte offset of 'mul' in the parsed input
+    pub offset: usize,
 }
 
 impl MultInstruction {
-    const MUL: &str = "mul";
-    const ENABLE: &str = "do()";
-    const DISABLE: &str = "don't()";
-
-    /// lhs*rhs
-    fn eval(&self) -> u32 {
-        self.lhs * self.rhs
+    pub const MUL: &str = "mul";
+    pub const ENABLE: &str = "do()";
+    pub const DISABLE: &str = "don't()";
+    /// Max. number of digits of an operand in the strict grammar
+    pub const MAX_DIGITS: usize = 3;
+
+    /// lhs*rhs, which always fits into u64
+    fn eval(&self) -> u64 {
+        u64::from(self.lhs) * u64::from(self.rhs)
     }
 
     /// Given a string tries to parse lhs and rhs
     /// that should come after the pattern 'mul'
-    /// and returns the byte length of the pattern "(lhs,rhs)"
-    fn parse(str: &str) -> Option<(Self, usize)> {
+    /// and returns the byte length of the pattern "(lhs,rhs)".
+    /// The offset is left to the caller since it is not known here.
+    pub fn parse(str: &str, mode: ParseMode) -> Option<(Self, usize)> {
+        match mode {
+            ParseMode::Strict => Self::parse_strict(str),
+            ParseMode::Lenient => Self::parse_lenient(str),
+        }
+    }
+
+    /// Parses exactly "(X,Y)" where X and Y are 1-3 ASCII digits
+    fn parse_strict(str: &str) -> Option<(Self, usize)> {
+        let bytes = str.as_bytes();
+        if bytes.first() != Some(&b'(') {
+            return None
+        }
+
+        let (lhs, len_lhs) = Self::parse_operand(&bytes[1..])?;
+        let idx_comma = 1 + len_lhs;
+        if bytes.get(idx_comma) != Some(&b',') {
+            return None
+        }
+
+        let (rhs, len_rhs) = Self::parse_operand(&bytes[(idx_comma + 1)..])?;
+        let idx_close = idx_comma + 1 + len_rhs;
+        if bytes.get(idx_close) != Some(&b')') {
+            return None
+        }
+
+        Some((Self { lhs, rhs, offset: 0 }, idx_close + 1))
+    }
+
+    /// Parses 1-3 ASCII digits at the start of the given bytes
+    /// and returns the operand with its byte length.
+    pub(super) fn parse_operand(bytes: &[u8]) -> Option<(u32, usize)> {
+        let len_digits = bytes.iter()
+            .take(Self::MAX_DIGITS + 1)
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if !(1..=Self::MAX_DIGITS).contains(&len_digits) {
+            return None
+        }
+
+        let operand = bytes[..len_digits].iter()
+            .fold(0, |acc, b| acc * 10 + u32::from(b - b'0'));
+        Some((operand, len_digits))
+    }
+
+    /// Parses an operand of [`ParseMode::Lenient`] at the start of the given bytes,
+    /// ASCII digits with an optional leading '+' that fit into u32, and returns
+    /// the operand with its byte length.
+    pub(super) fn parse_operand_lenient(bytes: &[u8]) -> Option<(u32, usize)> {
+        let len_sign = usize::from(bytes.first() == Some(&b'+'));
+        let len_digits = bytes[len_sign..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let len_operand = len_sign + len_digits;
+        let operand = std::str::from_utf8(&bytes[..len_operand]).ok()?.parse().ok()?;
+        Some((operand, len_operand))
+    }
+
+    /// Older parsing that accepts anything `str::parse::<u32>` accepts
+    /// between the first '(' , ',' and ')'. Kept for compatibility.
+    fn parse_lenient(str: &str) -> Option<(Self, usize)> {
         // Parse '(' right after the 'mul' instruction
         if !str.starts_with('(') {
             return None
@@ -44,11 +118,11 @@ impl MultInstruction {
         let rhs: u32 = str.get((idx_comma + 1)..idx_close)?
             .parse().ok()?;
 
-        Some((Self { lhs, rhs }, idx_close + 1))
+        Some((Self { lhs, rhs, offset: 0 }, idx_close + 1))
     }
 
     /// Parses multiple mul instructions from a string slice
-    fn parse_muls(str: &str) -> Vec<Self> {
+    pub fn parse_muls(str: &str, mode: ParseMode) -> Vec<Self> {
         let mut muls: Vec<Self> = Vec::new();
 
         // We will iterate the whole string and find mul instructions.
@@ -63,9 +137,9 @@ impl MultInstruction {
                 let idx_operands = idx_mul + Self::MUL.len();
 
                 // Try to parse the operands
-                match Self::parse(&str[idx_operands..]) {
+                match Self::parse(&str[idx_operands..], mode) {
                     Some((mul, len_operands)) => {
-                        muls.push(mul);
+                        muls.push(Self { offset: idx_mul, ..mul });
                         idx = idx_operands + len_operands;
                     },
                     None => idx = idx_operands,
@@ -78,7 +152,7 @@ impl MultInstruction {
         muls
     }
 
-    fn parse_muls_with_instruction(str: &str) -> Vec<Self> {
+    pub fn parse_muls_with_instruction(str: &str, mode: ParseMode) -> Vec<Self> {
         let mut muls = Vec::new();
 
         // We will iterate the whole string and find mul instructions
@@ -92,10 +166,13 @@ impl MultInstruction {
                     break;
                 }
 
-                // Just parse till we get a 'do()' instruction
+                // Just parse till we get a 'do()' instruction,
+                // without one the remaining muls are never enabled again
                 if let Some(idx_enable) = str[idx..].find(Self::ENABLE) {
                     is_enabled = true;
                     idx += idx_enable + Self::ENABLE.len();
+                } else {
+                    break;
                 }
             } else {
                 // Parse 'mul'
@@ -118,9 +195,9 @@ impl MultInstruction {
 
                     let idx_operands = idx_mul + Self::MUL.len();
 
-                    match Self::parse(&str[idx_operands..]) {
+                    match Self::parse(&str[idx_operands..], mode) {
                         Some((mul, len_operands)) => {
-                            muls.push(mul);
+                            muls.push(Self { offset: idx_mul, ..mul });
                             idx = idx_operands + len_operands;
                         },
                         None => idx = idx_operands,
@@ -136,98 +213,280 @@ impl MultInstruction {
 
     /// Parses multiple mul instructions from a string slice
     /// using regex
-    fn parse_muls_regex(input: &str) -> Vec<Self> {
-        let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
+    pub fn parse_muls_regex(input: &str, mode: ParseMode) -> Vec<Self> {
+        let re = Regex::new(Self::regex_pattern(mode)).unwrap();
         re.captures_iter(input)
-            .filter_map(|cap| {
-                let lhs = cap[1].parse::<u32>().ok()?; // 0 index is all matched strings together
-                let rhs = cap[2].parse::<u32>().ok()?;
-                Some(MultInstruction { lhs, rhs })
-            })
+            .filter_map(|cap| Self::from_captures(&cap))
             .collect()
     }
 
+    /// Parses multiple mul instructions from a string slice using
+    /// a regex alternation of 'mul(X,Y)', 'do()' and "don't()"
+    pub fn parse_muls_with_instruction_regex(input: &str, mode: ParseMode) -> Vec<Self> {
+        let pattern = format!(r"{}|{}|{}",
+            Self::regex_pattern(mode),
+            regex::escape(Self::ENABLE),
+            regex::escape(Self::DISABLE),
+        );
+        let re = Regex::new(&pattern).unwrap();
+
+        let mut muls = Vec::new();
+        let mut is_enabled = true;
+        for cap in re.captures_iter(input) {
+            match &cap[0] {
+                Self::ENABLE => is_enabled = true,
+                Self::DISABLE => is_enabled = false,
+                _ if is_enabled => muls.extend(Self::from_captures(&cap)),
+                _ => {},
+            }
+        }
+
+        muls
+    }
+
+    /// Pattern of 'mul(X,Y)' where the digits of X and Y are captured
+    fn regex_pattern(mode: ParseMode) -> &'static str {
+        match mode {
+            ParseMode::Strict => r"mul\(([0-9]{1,3}),([0-9]{1,3})\)",
+            // Same grammar as `str::parse::<u32>`, operands out of range are dropped in `from_captures`
+            ParseMode::Lenient => r"mul\(\+?([0-9]+),\+?([0-9]+)\)",
+        }
+    }
+
+    /// Builds the instruction from the captures of [`Self::regex_pattern`]
+    fn from_captures(cap: &regex::Captures) -> Option<Self> {
+        let offset = cap.get(0)?.start(); // 0 index is all matched strings together
+        let lhs = cap.get(1)?.as_str().parse::<u32>().ok()?;
+        let rhs = cap.get(2)?.as_str().parse::<u32>().ok()?;
+        Some(MultInstruction { lhs, rhs, offset })
+    }
+
     /// We evaluate each mul and reduce with summation.
-    fn sum_muls(muls: &[Self]) -> u32 {
-        muls.iter().map(|mul| mul.eval()).sum()
+    /// Fails with the offset of the mul that overflows the sum.
+    pub fn sum_muls(muls: &[Self]) -> Result<u64> {
+        muls.iter().try_fold(0u64, |sum, mul| {
+            sum.checked_add(mul.eval())
+                .ok_or(Error::ArithmeticOverflow { offset: mul.offset })
+        })
     }
 }
 
-/// Reads the input file, parses the mul instructions, evaluates
+/// Parses the mul instructions of the input, evaluates
 /// them one by one and reduces the result into a sum.
-pub fn get_sum_of_mults() -> Result<u32> {
-    let input_str: String = read_input_file()?;
-    let muls: Vec<MultInstruction> = MultInstruction::parse_muls(&input_str);
-    let sum = MultInstruction::sum_muls(&muls);
-    Ok(sum)
+pub fn get_sum_of_mults(input: &str, mode: ParseMode) -> Result<u64> {
+    let muls: Vec<MultInstruction> = MultInstruction::parse_muls(input, mode);
+    MultInstruction::sum_muls(&muls)
 }
 
-/// Reads the input file, parses the mul instructions, evaluates
+/// Parses the mul instructions of the input using regex, evaluates
 /// them one by one and reduces the result into a sum.
-pub fn get_sum_of_mults_regex() -> Result<u32> {
-    let input_str: String = read_input_file()?;
-    let muls: Vec<MultInstruction> = MultInstruction::parse_muls_regex(&input_str);
-    let sum = MultInstruction::sum_muls(&muls);
-    Ok(sum)
+pub fn get_sum_of_mults_regex(input: &str, mode: ParseMode) -> Result<u64> {
+    let muls: Vec<MultInstruction> = MultInstruction::parse_muls_regex(input, mode);
+    MultInstruction::sum_muls(&muls)
 }
 
-/// Reads the input file, parses mul, enable and disable instructions,
+/// Parses the mul, enable and disable instructions of the input,
 /// evaluates them one by one and reduces the result into a sum.
-pub fn get_sum_of_mults_with_instructions() -> Result<u32> {
-    let input_str: String = read_input_file()?;
-    let muls: Vec<MultInstruction> = MultInstruction::parse_muls_with_instruction(&input_str);
-    let sum = MultInstruction::sum_muls(&muls);
-    Ok(sum)
+pub fn get_sum_of_mults_with_instructions(input: &str, mode: ParseMode) -> Result<u64> {
+    let muls: Vec<MultInstruction> = MultInstruction::parse_muls_with_instruction(input, mode);
+    MultInstruction::sum_muls(&muls)
+}
+
+/// Parses the mul, enable and disable instructions of the input using regex,
+/// evaluates them one by one and reduces the result into a sum.
+pub fn get_sum_of_mults_with_instructions_regex(input: &str, mode: ParseMode) -> Result<u64> {
+    let muls: Vec<MultInstruction> = MultInstruction::parse_muls_with_instruction_regex(input, mode);
+    MultInstruction::sum_muls(&muls)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::day3_mull_it_over::marker_scanner::MarkerScanner;
+    use crate::day3_mull_it_over::parse_muls_reference;
+    use proptest::prelude::*;
 
     #[test]
     fn should_parse_correct_input() {
         let input = "mul(2,3)mul(4,5)mul(6,7)";
-        let muls = MultInstruction::parse_muls(input);
-        let sum = MultInstruction::sum_muls(&muls);
+        let muls = MultInstruction::parse_muls(input, ParseMode::Strict);
+        let sum = MultInstruction::sum_muls(&muls).unwrap();
         assert_eq!(sum, 2*3 + 4*5 + 6*7);
 
         // regex version
-        let muls = MultInstruction::parse_muls_regex(input);
-        let sum = MultInstruction::sum_muls(&muls);
+        let muls = MultInstruction::parse_muls_regex(input, ParseMode::Strict);
+        let sum = MultInstruction::sum_muls(&muls).unwrap();
         assert_eq!(sum, 2*3 + 4*5 + 6*7);
     }
 
     #[test]
     fn should_parse_wrong_input() {
         let input = "aeuiaeuasjkdmulafj(ksdfj)sfjkmu(sd,2)mul(12,)mulmul(234,242a)msdflul2";
-        let muls = MultInstruction::parse_muls(input);
-        let sum = MultInstruction::sum_muls(&muls);
+        let muls = MultInstruction::parse_muls(input, ParseMode::Strict);
+        let sum = MultInstruction::sum_muls(&muls).unwrap();
         assert_eq!(sum, 0);
 
         // regex version
-        let muls = MultInstruction::parse_muls_regex(input);
-        let sum = MultInstruction::sum_muls(&muls);
+        let muls = MultInstruction::parse_muls_regex(input, ParseMode::Strict);
+        let sum = MultInstruction::sum_muls(&muls).unwrap();
         assert_eq!(sum, 0);
     }
 
     #[test]
     fn should_parse_mixed_input() {
         let input = "randomtextmul(2,3)garbage";
-        let muls = MultInstruction::parse_muls(input);
-        let sum = MultInstruction::sum_muls(&muls);
+        let muls = MultInstruction::parse_muls(input, ParseMode::Strict);
+        let sum = MultInstruction::sum_muls(&muls).unwrap();
         assert_eq!(sum, 2*3);
 
         // regex version
-        let muls = MultInstruction::parse_muls_regex(input);
-        let sum = MultInstruction::sum_muls(&muls);
+        let muls = MultInstruction::parse_muls_regex(input, ParseMode::Strict);
+        let sum = MultInstruction::sum_muls(&muls).unwrap();
         assert_eq!(sum, 2*3);
     }
 
     #[test]
     fn should_parse_with_instruction() {
         let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-        let muls = MultInstruction::parse_muls_with_instruction(input);
-        let sum = MultInstruction::sum_muls(&muls);
+        let muls = MultInstruction::parse_muls_with_instruction(input, ParseMode::Strict);
+        let sum = MultInstruction::sum_muls(&muls).unwrap();
+        assert_eq!(sum, 48);
+
+        // regex version
+        let muls = MultInstruction::parse_muls_with_instruction_regex(input, ParseMode::Strict);
+        let sum = MultInstruction::sum_muls(&muls).unwrap();
         assert_eq!(sum, 48);
     }
+
+    #[test]
+    fn should_stop_when_no_enable_follows() {
+        // Used to loop forever: disabled with a 'mul' left but no 'do()' after it
+        let input = "mul(2,3)don't()mul(1,2)";
+        let muls = MultInstruction::parse_muls_with_instruction(input, ParseMode::Strict);
+        assert_eq!(MultInstruction::sum_muls(&muls).unwrap(), 6);
+
+        let muls = MultInstruction::parse_muls_with_instruction("don't()mul(1,2)do", ParseMode::Lenient);
+        assert!(muls.is_empty());
+    }
+
+    #[test]
+    fn should_agree_with_instructions() {
+        let inputs = [
+            "don't()mul(1,2)",
+            "don't()do()mul(1,2)don't()",
+            "do()don't()mul(1,2)do()mul(3,4)",
+            "mul(1,don't()2)mul(3,4)do()mul(5,6)",
+            "don't(mul(1,2)do(mul(3,4)",
+            "mul(2,3)don't()do()don't()",
+            "don't()xmul(2,3)do()mul(1000,2)",
+        ];
+        for input in inputs {
+            let muls = MultInstruction::parse_muls_with_instruction(input, ParseMode::Strict);
+            let sum = MultInstruction::sum_muls(&muls).unwrap();
+            let muls = MultInstruction::parse_muls_with_instruction_regex(input, ParseMode::Strict);
+            let sum_regex = MultInstruction::sum_muls(&muls).unwrap();
+            assert_eq!(sum, sum_regex, "{input}");
+        }
+    }
+
+    /// Edge cases of the grammar with the expected sum in strict mode,
+    /// both the hand-written and the regex parser should agree on these.
+    const EDGE_CASES: [(&str, u64); 16] = [
+        ("mul(1,2)", 2),
+        ("mul(123,456)", 123 * 456),
+        ("mul(999,999)", 999 * 999),
+        ("mul(1234,5)", 0),
+        ("mul(5,1234)", 0),
+        ("mul(+1,2)", 0),
+        ("mul(1,-2)", 0),
+        ("mul( 1,2)", 0),
+        ("mul(1,2 )", 0),
+        ("mul(,2)", 0),
+        ("mul(1,)", 0),
+        ("mul(1)mul(2,3)", 6),
+        ("mul(2)x,3)", 0),
+        ("mul(1,2,3)mul(4,5)", 20),
+        ("mul(mul(2,3)mulmul(4,5)", 26),
+        ("mul(\u{0661},2)mul(007,010)", 70),
+    ];
+
+    #[test]
+    fn should_agree_on_edge_cases() {
+        for (input, expected) in EDGE_CASES {
+            let muls = MultInstruction::parse_muls(input, ParseMode::Strict);
+            assert_eq!(MultInstruction::sum_muls(&muls).unwrap(), expected, "hand-written: {input}");
+
+            let muls = MultInstruction::parse_muls_regex(input, ParseMode::Strict);
+            assert_eq!(MultInstruction::sum_muls(&muls).unwrap(), expected, "regex: {input}");
+        }
+    }
+
+    #[test]
+    fn should_parse_leniently() {
+        let input = "mul(+1,2)mul(1234,5)mul(2)x,3)mul(++1,2)mul(-1,2)mul(\u{0661},2)mul(4294967296,1)mul(1,+0004294967295)";
+        let muls = MultInstruction::parse_muls(input, ParseMode::Lenient);
+        assert_eq!(MultInstruction::sum_muls(&muls).unwrap(), 2 + 1234 * 5 + 4294967295);
+
+        // The lenient regex accepts exactly the same operands
+        let muls_regex = MultInstruction::parse_muls_regex(input, ParseMode::Lenient);
+        assert_eq!(muls_regex, muls);
+        let muls_regex = MultInstruction::parse_muls_with_instruction_regex(input, ParseMode::Lenient);
+        assert_eq!(muls_regex, MultInstruction::parse_muls_with_instruction(input, ParseMode::Lenient));
+    }
+
+    #[test]
+    fn should_report_overflow_offset() {
+        let input = "mul(4294967295,4294967295)xmul(4294967295,4294967295)";
+        let muls = MultInstruction::parse_muls(input, ParseMode::Lenient);
+        assert_eq!(muls[0].eval(), 18446744065119617025);
+        assert!(matches!(
+            MultInstruction::sum_muls(&muls),
+            Err(Error::ArithmeticOverflow { offset: 27 })
+        ));
+    }
+
+    /// Corrupted memory made of pieces of the instructions, digits and noise
+    fn memory() -> impl Strategy<Value = String> {
+        let piece = prop::sample::select(vec![
+            "mul", "(", ")", ",", "do()", "don't()", "do", "n't", "1", "23", "456", "7890", "+", "-", " ", "x", "ü",
+        ]);
+        prop::collection::vec(piece, 0..24).prop_map(|pieces| pieces.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn should_parse_muls_like_reference(input in memory()) {
+            let expected = parse_muls_reference(&input, false);
+            prop_assert_eq!(&MultInstruction::parse_muls(&input, ParseMode::Strict), &expected);
+            prop_assert_eq!(&MultInstruction::parse_muls_regex(&input, ParseMode::Strict), &expected);
+            prop_assert_eq!(&MarkerScanner::parse_muls(&input, ParseMode::Strict, false), &expected);
+        }
+
+        #[test]
+        fn should_parse_muls_with_instructions_like_reference(input in memory()) {
+            let expected = parse_muls_reference(&input, true);
+            prop_assert_eq!(&MultInstruction::parse_muls_with_instruction(&input, ParseMode::Strict), &expected);
+            prop_assert_eq!(&MultInstruction::parse_muls_with_instruction_regex(&input, ParseMode::Strict), &expected);
+            prop_assert_eq!(&MarkerScanner::parse_muls(&input, ParseMode::Strict, true), &expected);
+        }
+
+        #[test]
+        fn should_accept_more_leniently(input in memory()) {
+            let strict = MultInstruction::parse_muls(&input, ParseMode::Strict);
+            let lenient = MultInstruction::parse_muls(&input, ParseMode::Lenient);
+            prop_assert!(strict.iter().all(|mul| lenient.contains(mul)));
+        }
+
+        #[test]
+        fn should_parse_leniently_like_regex(input in memory()) {
+            prop_assert_eq!(
+                MultInstruction::parse_muls(&input, ParseMode::Lenient),
+                MultInstruction::parse_muls_regex(&input, ParseMode::Lenient),
+            );
+            prop_assert_eq!(
+                MultInstruction::parse_muls_with_instruction(&input, ParseMode::Lenient),
+                MultInstruction::parse_muls_with_instruction_regex(&input, ParseMode::Lenient),
+            );
+        }
+    }
 }
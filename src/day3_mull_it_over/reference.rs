@@ -0,0 +1,37 @@
+//! Byte by byte scan of the strict grammar, used for the generated answers and to test the parsers
+
+use super::MultInstruction;
+
+
+/// Naive reference of the strict grammar that tries every byte offset,
+/// 'do()' and "don't()" are only applied `with_instructions`
+pub(super) fn parse_muls_reference(input: &str, with_instructions: bool) -> Vec<MultInstruction> {
+    // 1-3 digits followed by the byte
+    fn operand(bytes: &[u8], end: u8) -> Option<(u32, &[u8])> {
+        let len_digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+        if !(1..=3).contains(&len_digits) || bytes.get(len_digits) != Some(&end) {
+            return None;
+        }
+        let operand = std::str::from_utf8(&bytes[..len_digits]).ok()?.parse().ok()?;
+        Some((operand, &bytes[(len_digits + 1)..]))
+    }
+
+    let bytes = input.as_bytes();
+    let mut is_enabled = true;
+    let mut muls = Vec::new();
+    for offset in 0..bytes.len() {
+        let rest = &bytes[offset..];
+        if rest.starts_with(b"do()") {
+            is_enabled = true;
+        } else if rest.starts_with(b"don't()") {
+            is_enabled = !with_instructions;
+        } else if let Some(rest) = rest.strip_prefix(b"mul(") {
+            let Some((lhs, rest)) = operand(rest, b',') else { continue };
+            let Some((rhs, _)) = operand(rest, b')') else { continue };
+            if is_enabled {
+                muls.push(MultInstruction { lhs, rhs, offset });
+            }
+        }
+    }
+    muls
+}
@@ -0,0 +1,240 @@
+use std::io::Read;
+use super::MultInstruction;
+use crate::{Error, Result};
+
+
+/// Why no instruction could be matched at a position
+enum Mismatch {
+    /// The bytes are not an instruction
+    Invalid,
+    /// The bytes end before the instruction can be decided, more input is needed
+    Incomplete,
+}
+
+/// A matched value together with its byte length
+type Scan<T> = std::result::Result<(T, usize), Mismatch>;
+
+/// Instructions recognised by the stream parser
+enum Token {
+    Mul { lhs: u32, rhs: u32 },
+    Enable,
+    Disable,
+}
+
+/// Resumable parser for the strict grammar that is fed the corrupted memory chunk by chunk.
+///
+/// Bytes of an instruction that may continue in the next chunk, e.g. "mul(12," or "don'",
+/// are kept until the instruction can be decided. The parsed muls are identical to
+/// [`MultInstruction::parse_muls`] and [`MultInstruction::parse_muls_with_instruction`].
+pub(super) struct StreamParser {
+    /// Whether 'do()' and "don't()" are applied as in part 2
+    with_instructions: bool,
+    is_enabled: bool,
+    /// Bytes that are not decided yet
+    buffer: Vec<u8>,
+    /// Byte offset of the start of the buffer in the whole stream
+    offset: usize,
+    muls: Vec<MultInstruction>,
+}
+
+impl StreamParser {
+    const CHUNK_SIZE: usize = 8 * 1024;
+
+    pub fn new(with_instructions: bool) -> Self {
+        Self { with_instructions, is_enabled: true, buffer: Vec::new(), offset: 0, muls: Vec::new() }
+    }
+
+    /// Parses the next chunk of the stream
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+        let len_decided = self.scan(false);
+        self.buffer.drain(..len_decided);
+        self.offset += len_decided;
+    }
+
+    /// Parses what is left at the end of the stream and returns all the muls
+    pub fn finish(mut self) -> Vec<MultInstruction> {
+        self.scan(true);
+        self.muls
+    }
+
+    /// Scans the buffer and returns the byte length that is decided.
+    /// At the end of the stream nothing is incomplete anymore.
+    fn scan(&mut self, is_end: bool) -> usize {
+        let mut idx: usize = 0;
+        while idx < self.buffer.len() {
+            match self.scan_token(&self.buffer[idx..]) {
+                Ok((token, len)) => {
+                    match token {
+                        Token::Mul { lhs, rhs } => {
+                            self.muls.push(MultInstruction { lhs, rhs, offset: self.offset + idx });
+                        },
+                        Token::Enable => self.is_enabled = true,
+                        Token::Disable => self.is_enabled = false,
+                    }
+                    idx += len;
+                },
+                Err(Mismatch::Incomplete) if !is_end => break,
+                Err(_) => idx += 1,
+            }
+        }
+        idx
+    }
+
+    /// Tries to match the instruction that matters in the current state
+    /// at the start of the bytes
+    fn scan_token(&self, bytes: &[u8]) -> Scan<Token> {
+        if !self.with_instructions {
+            return scan_mul(bytes);
+        }
+
+        if self.is_enabled {
+            match bytes.first() {
+                Some(b'd') => scan_literal(bytes, MultInstruction::DISABLE).map(|(_, len)| (Token::Disable, len)),
+                _ => scan_mul(bytes),
+            }
+        } else {
+            // Muls are skipped anyway, so only 'do()' matters
+            scan_literal(bytes, MultInstruction::ENABLE).map(|(_, len)| (Token::Enable, len))
+        }
+    }
+}
+
+/// Matches "mul(X,Y)" where X and Y are 1-3 ASCII digits
+fn scan_mul(bytes: &[u8]) -> Scan<Token> {
+    let (_, mut idx) = scan_literal(bytes, MultInstruction::MUL)?;
+    idx += scan_literal(&bytes[idx..], "(")?.1;
+    let (lhs, len_lhs) = scan_operand(&bytes[idx..])?;
+    idx += len_lhs;
+    idx += scan_literal(&bytes[idx..], ",")?.1;
+    let (rhs, len_rhs) = scan_operand(&bytes[idx..])?;
+    idx += len_rhs;
+    idx += scan_literal(&bytes[idx..], ")")?.1;
+    Ok((Token::Mul { lhs, rhs }, idx))
+}
+
+fn scan_literal(bytes: &[u8], literal: &str) -> Scan<()> {
+    let literal = literal.as_bytes();
+    if bytes.starts_with(literal) {
+        Ok(((), literal.len()))
+    } else if literal.starts_with(bytes) {
+        Err(Mismatch::Incomplete)
+    } else {
+        Err(Mismatch::Invalid)
+    }
+}
+
+fn scan_operand(bytes: &[u8]) -> Scan<u32> {
+    match MultInstruction::parse_operand(bytes) {
+        // More digits may follow in the next chunk
+        Some((_, len_digits)) if len_digits == bytes.len() => Err(Mismatch::Incomplete),
+        Some(operand) => Ok(operand),
+        None if bytes.is_empty() => Err(Mismatch::Incomplete),
+        None => Err(Mismatch::Invalid),
+    }
+}
+
+/// Parses the muls from any reader chunk by chunk
+fn parse_muls_from_reader(mut reader: impl Read, with_instructions: bool) -> Result<Vec<MultInstruction>> {
+    let mut parser = StreamParser::new(with_instructions);
+    let mut chunk = vec![0; StreamParser::CHUNK_SIZE];
+    loop {
+        match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(len) => parser.feed(&chunk[..len]),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(Error::CannotReadFile { msg: e.to_string() }),
+        }
+    }
+    Ok(parser.finish())
+}
+
+/// Streams the input from the reader instead of reading it into memory and reduces the
+/// mul results into a sum. With instructions, 'do()' and "don't()" are applied as in part 2.
+pub fn get_sum_of_mults_streaming(reader: impl Read, with_instructions: bool) -> Result<u64> {
+    let muls = parse_muls_from_reader(reader, with_instructions)?;
+    MultInstruction::sum_muls(&muls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day3_mull_it_over::ParseMode;
+    use proptest::prelude::*;
+
+    const INPUTS: [&str; 6] = [
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
+        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))",
+        "mul(123,456)mul(1234,5)mul(12,3456)mulmul(1,2)mul(mul(3,4)",
+        "don't()do()don't()mul(1,2)do(mul(3,4)do()mul(5,6)don'tmul(7,8)",
+        "do()don't(do()mul(999,999)don't()",
+        "mul(1,2",
+    ];
+
+    fn parse_in_memory(input: &str, with_instructions: bool) -> Vec<MultInstruction> {
+        if with_instructions {
+            MultInstruction::parse_muls_with_instruction(input, ParseMode::Strict)
+        } else {
+            MultInstruction::parse_muls(input, ParseMode::Strict)
+        }
+    }
+
+    #[test]
+    fn should_agree_when_split_at_every_offset() {
+        for input in INPUTS {
+            for with_instructions in [false, true] {
+                let expected = parse_in_memory(input, with_instructions);
+                for idx_split in 0..=input.len() {
+                    let mut parser = StreamParser::new(with_instructions);
+                    parser.feed(&input.as_bytes()[..idx_split]);
+                    parser.feed(&input.as_bytes()[idx_split..]);
+                    assert_eq!(parser.finish(), expected, "{input} split at {idx_split}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn should_agree_when_fed_byte_by_byte() {
+        for input in INPUTS {
+            for with_instructions in [false, true] {
+                let mut parser = StreamParser::new(with_instructions);
+                for byte in input.as_bytes() {
+                    parser.feed(std::slice::from_ref(byte));
+                }
+                assert_eq!(parser.finish(), parse_in_memory(input, with_instructions), "{input}");
+            }
+        }
+    }
+
+    #[test]
+    fn should_parse_from_reader() {
+        let input = INPUTS.join("").repeat(500);
+        for with_instructions in [false, true] {
+            let muls = parse_muls_from_reader(input.as_bytes(), with_instructions).unwrap();
+            assert_eq!(muls, parse_in_memory(&input, with_instructions));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn should_agree_when_fed_random_chunks(
+            pieces in prop::collection::vec(prop::sample::select(vec!["mul(", "12", "3", ",", ")", "do()", "don't()", "don", "x"]), 0..24),
+            len_chunks in prop::collection::vec(1..8usize, 1..8),
+            with_instructions: bool,
+        ) {
+            let input = pieces.concat();
+            let mut parser = StreamParser::new(with_instructions);
+            let mut bytes = input.as_bytes();
+            for &len_chunk in len_chunks.iter().cycle() {
+                if bytes.is_empty() {
+                    break;
+                }
+                let (chunk, rest) = bytes.split_at(len_chunk.min(bytes.len()));
+                parser.feed(chunk);
+                bytes = rest;
+            }
+            prop_assert_eq!(parser.finish(), parse_in_memory(&input, with_instructions));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use super::{Instruction, Machine, ParseMode};
+use crate::Result;
+
+
+/// A single executed instruction and the machine state right after it
+pub(super) struct TraceStep {
+    /// Byte offset of the instruction in the corrupted memory
+    pub offset: usize,
+    pub instruction: Instruction,
+    /// Whether the machine is enabled after the instruction
+    pub enabled: bool,
+    /// Result of the opcode, e.g. the product of 'mul', `None` if it was not applied
+    pub result: Option<u64>,
+    /// Accumulator after the instruction
+    pub total: u64,
+}
+
+impl std::fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = if self.enabled { "enabled" } else { "disabled" };
+        let result = self.result.map_or("-".to_string(), |result| result.to_string());
+        write!(f, "{:>8}  {:<8}  {:<20}  {:>11}  {:>19}",
+            self.offset, state, self.instruction.to_string(), result, self.total)
+    }
+}
+
+/// Executes the corrupted memory from a fresh state
+/// and records every recognised instruction.
+pub(super) fn trace(machine: &mut Machine, memory: &str) -> Result<Vec<TraceStep>> {
+    machine.reset();
+
+    let mut steps = Vec::new();
+    for (offset, instruction) in machine.parse(memory) {
+        let result = machine.execute(offset, &instruction)?;
+        let state = machine.state();
+        steps.push(TraceStep { offset, instruction, enabled: state.enabled, result, total: state.accumulator });
+    }
+
+    Ok(steps)
+}
+
+/// Strips the noise from the corrupted memory and returns
+/// the recognised instructions, one per line.
+pub(super) fn disassemble(machine: &Machine, memory: &str) -> String {
+    machine.parse(memory)
+        .iter()
+        .map(|(_, instruction)| format!("{instruction}\n"))
+        .collect()
+}
+
+/// Returns the part 2 execution trace of the input, parsed in the given mode, as a table
+pub fn get_trace(input: &str, mode: ParseMode) -> Result<String> {
+    let steps = trace(&mut Machine::new().with_parse_mode(mode), input)?;
+
+    let mut listing = format!("{:>8}  {:<8}  {:<20}  {:>11}  {:>19}\n",
+        "offset", "state", "instruction", "result", "total");
+    for step in steps {
+        listing += &format!("{step}\n");
+    }
+    Ok(listing)
+}
+
+/// Returns the clean program listing of the input, parsed in the given mode
+pub fn get_disassembly(input: &str, mode: ParseMode) -> String {
+    disassemble(&Machine::new().with_parse_mode(mode), input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn should_trace_example() {
+        let steps = trace(&mut Machine::new(), EXAMPLE).unwrap();
+        let rows: Vec<(usize, bool, Option<u64>, u64)> = steps.iter()
+            .map(|step| (step.offset, step.enabled, step.result, step.total))
+            .collect();
+        assert_eq!(rows, vec![
+            (1, true, Some(8), 8),
+            (20, false, None, 8),
+            (28, false, None, 8),
+            (48, false, None, 8),
+            (59, true, None, 8),
+            (64, true, Some(40), 48),
+        ]);
+    }
+
+    #[test]
+    fn should_trace_results_of_registered_opcodes() {
+        // Both lower the accumulator, but their results are still shown
+        let mut machine = Machine::new();
+        machine.register("reset", 0, |state, _| {
+            state.accumulator = 0;
+            Some(0)
+        });
+        machine.register("half", 1, |state, args| {
+            state.accumulator /= args[0];
+            Some(state.accumulator)
+        });
+        let steps = trace(&mut machine, "mul(2,3)reset()mul(5,2)half(2)don't()mul(1,1)").unwrap();
+        let rows: Vec<(Option<u64>, u64)> = steps.iter()
+            .map(|step| (step.result, step.total))
+            .collect();
+        assert_eq!(rows, vec![(Some(6), 6), (Some(0), 0), (Some(10), 10), (Some(5), 5), (None, 5), (None, 5)]);
+    }
+
+    #[test]
+    fn should_disassemble_example() {
+        let machine = Machine::new();
+        let listing = disassemble(&machine, EXAMPLE);
+        assert_eq!(listing, "mul(2,4)\ndon't()\nmul(5,5)\nmul(11,8)\ndo()\nmul(8,5)\n");
+
+        // The listing is a program on its own with the same result
+        let mut machine = Machine::new();
+        assert_eq!(machine.run(&listing).unwrap(), machine.run(EXAMPLE).unwrap());
+    }
+}
@@ -0,0 +1,194 @@
+use aho_corasick::AhoCorasick;
+use super::{read_area_with, Direction, Matrix, SearchArea, WordMatch};
+use crate::grid::{Addressing, Point};
+use crate::{Error, Result};
+
+
+/// All the occurrences of one word of a dictionary
+#[derive(Debug, PartialEq, Eq)]
+pub struct DictionaryMatches<'a> {
+    pub word: &'a str,
+    /// Sorted in the same order as [`Matrix::find_word`] returns them
+    pub matches: Vec<WordMatch>,
+}
+
+/// A straight line through the matrix that is read in one pass
+struct Line {
+    direction: Direction,
+    points: Vec<Point>,
+}
+
+/// Implements the dictionary search
+impl Matrix {
+    /// Directions in which all the lines are read, the reversed ones are covered by reversed words
+    const FORWARD: [Direction; 4] = [Direction::Right, Direction::Down, Direction::DownRight, Direction::DownLeft];
+
+    /// Every row, column, diagonal and anti-diagonal, read in the forward directions
+    fn lines(&self) -> Vec<Line> {
+        Self::FORWARD.iter()
+            .flat_map(|&direction| {
+                let (step_row, step_col) = direction.step();
+                self.grid().lines(step_row, step_col)
+                    .map(move |points| Line { direction, points: points.collect() })
+            })
+            .collect()
+    }
+
+    /// Letters of the line as UTF-8 with the byte offset of each letter
+    fn text_of(&self, line: &Line) -> (String, Vec<usize>) {
+        let mut text = String::with_capacity(line.points.len());
+        let mut offsets = Vec::with_capacity(line.points.len());
+        for &point in &line.points {
+            offsets.push(text.len());
+            text.push(self.grid()[point]);
+        }
+        (text, offsets)
+    }
+
+    /// Returns all the occurrences of all the words in all eight directions, in the order
+    /// of the given words. Every line of the matrix is scanned only once for all the words
+    /// and their reversals, which is much faster than [`Matrix::find_word`] per word,
+    /// except with toroidal addressing.
+    pub fn find_words<'a>(&self, words: &[&'a str]) -> Result<Vec<DictionaryMatches<'a>>> {
+        // On a torus the lines continue into each other, so every word is searched on its own
+        if self.addressing() == Addressing::Toroidal {
+            return Ok(words.iter()
+                .map(|&word| DictionaryMatches { word, matches: self.find_word(word) })
+                .collect());
+        }
+
+        // Empty words are never found
+        let searchable: Vec<usize> = (0..words.len()).filter(|&idx| !words[idx].is_empty()).collect();
+
+        // Pattern 2 * i is the word i of the searchable ones, 2 * i + 1 the reversed word
+        let patterns: Vec<String> = searchable.iter()
+            .flat_map(|&idx| {
+                let letters: String = words[idx].chars().map(|letter| self.normalize(letter)).collect();
+                let reversed: String = letters.chars().rev().collect();
+                [letters, reversed]
+            })
+            .collect();
+        let automaton = AhoCorasick::new(&patterns)
+            .map_err(|e| Error::InvalidArgument { arg: e.to_string() })?;
+
+        let mut dictionary: Vec<DictionaryMatches> = words.iter()
+            .map(|&word| DictionaryMatches { word, matches: Vec::new() })
+            .collect();
+        for line in self.lines() {
+            let (text, offsets) = self.text_of(&line);
+            for found in automaton.find_overlapping_iter(&text) {
+                let idx_pattern = found.pattern().as_usize();
+                let is_reversed = idx_pattern % 2 == 1;
+                let idx_word = searchable[idx_pattern / 2];
+
+                // UTF-8 matches always start and end on letter boundaries
+                let first = offsets.partition_point(|&offset| offset < found.start());
+                let last = offsets.partition_point(|&offset| offset < found.end()) - 1;
+
+                // A single letter is only found once per cell, in the first direction like `find_word` does
+                if first == last && (is_reversed || line.direction != Direction::ALL[0]) {
+                    continue;
+                }
+
+                let (distance, direction) = if is_reversed {
+                    (last, line.direction.reversed())
+                } else {
+                    (first, line.direction)
+                };
+                let Point { row: idx_row, col: idx_col } = line.points[distance];
+                dictionary[idx_word].matches.push(WordMatch { idx_row, idx_col, direction });
+            }
+        }
+
+        for word in &mut dictionary {
+            word.matches.sort_unstable();
+        }
+        Ok(dictionary)
+    }
+}
+
+/// Reads the input file and reports for every word how often and where it occurs, e.g.
+///
+/// XMAS: 2 at (0, 4) right, (9, 3) up_left
+///
+/// Only the `area` is searched. If `fold_case` is set the case of the letters is ignored.
+pub fn get_dictionary_matches(area: SearchArea, words: &[String], fold_case: bool) -> Result<String> {
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let dictionary = read_area_with(area, fold_case)?.find_words(&words)?;
+
+    let mut report = String::new();
+    for DictionaryMatches { word, matches } in dictionary {
+        let positions: Vec<String> = matches.iter()
+            .map(|m| format!("({}, {}) {}", m.idx_row, m.idx_col, m.direction.name()))
+            .collect();
+        report += &format!("{word}: {}", matches.len());
+        if !positions.is_empty() {
+            report += &format!(" at {}", positions.join(", "));
+        }
+        report += "\n";
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+        MMMSXXMASM
+        MSAMXMSMSA
+        AMXSXMAAMM
+        MSAMASMSMX
+        XMASAMXAMM
+        XXAMMXXAMA
+        SMSMSASXSS
+        SAXAMASAAA
+        MAMMMXMMMM
+        MXMXAXMASX
+    ";
+
+    #[test]
+    fn should_agree_with_single_word_search() {
+        let words = ["XMAS", "MAS", "SAMX", "AMA", "S", "XMAS", "", "XMAZ", "MMMSXXMASM", "MASAM"];
+        for data in [EXAMPLE, "XMAS", "X\nM\nA\nS", "XM\nMA", ""] {
+            let matrix: Matrix = data.parse().unwrap();
+            let dictionary = matrix.find_words(&words).unwrap();
+            assert_eq!(dictionary.len(), words.len());
+            for DictionaryMatches { word, matches } in dictionary {
+                assert_eq!(matches, matrix.find_word(word), "{word} in {data}");
+            }
+        }
+    }
+
+    #[test]
+    fn should_count_per_word() {
+        let matrix: Matrix = EXAMPLE.parse().unwrap();
+        let counts: Vec<usize> = matrix.find_words(&["XMAS", "MAS"]).unwrap().iter()
+            .map(|word| word.matches.len())
+            .collect();
+        assert_eq!(counts, vec![18, matrix.count_word("MAS")]);
+    }
+
+    #[test]
+    fn should_search_any_alphabet() {
+        let data = "
+            ÄPFEL.
+            ßXQ€ΣΙ
+            LEFPÄ#
+        ";
+        let words = ["ÄPFEL", "äpfel", "Σ", "€Q", "ΣΙ"];
+        let counts = |matrix: &Matrix| -> Vec<usize> {
+            matrix.find_words(&words).unwrap().iter().map(|word| word.matches.len()).collect()
+        };
+
+        let matrix: Matrix = data.parse().unwrap();
+        assert_eq!(counts(&matrix), vec![2, 0, 1, 1, 1]);
+        assert_eq!(matrix.find_words(&["€Q"]).unwrap()[0].matches, matrix.find_word("€Q"));
+
+        let matrix = Matrix::parse_with(data, true).unwrap();
+        assert_eq!(counts(&matrix), vec![2, 2, 1, 1, 1]);
+        for word in words {
+            assert_eq!(matrix.find_words(&[word]).unwrap()[0].matches, matrix.find_word(word), "{word}");
+        }
+    }
+}
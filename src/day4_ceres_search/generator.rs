@@ -0,0 +1,316 @@
+use std::collections::HashSet;
+use std::path::Path;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{RngExt, SeedableRng};
+use rand_xorshift::XorShiftRng;
+use super::{Direction, XMAS};
+use crate::grid::{Grid, Point};
+use crate::synthetic::SyntheticInput;
+use crate::{Error, Result};
+
+
+/// What a generated word search looks like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleSpec {
+    pub nrows: usize,
+    pub ncols: usize,
+    /// Letters of the noise around the planted words, they may include the XMAS letters
+    pub alphabet: Vec<char>,
+    /// Number of "XMAS" that are planted
+    pub xmas: usize,
+    /// Number of crossing "MAS" that are planted
+    pub crosses: usize,
+    /// Directions that the planted "XMAS" are read in, chosen at random per word
+    pub directions: Vec<Direction>,
+    /// The same seed always generates the same puzzle
+    pub seed: u64,
+}
+
+impl Default for PuzzleSpec {
+    fn default() -> Self {
+        Self {
+            nrows: 140,
+            ncols: 140,
+            alphabet: XMAS.chars().collect(),
+            xmas: 10,
+            crosses: 10,
+            directions: Direction::ALL.to_vec(),
+            seed: 0,
+        }
+    }
+}
+
+/// A word search with its known answers for both parts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedPuzzle {
+    pub grid: Grid<char>,
+    pub xmas: usize,
+    pub crosses: usize,
+}
+
+impl GeneratedPuzzle {
+    /// Writes the grid to the path and the expected answers next to it, e.g. to 'grid.txt.expected'
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut path_expected = path.as_os_str().to_owned();
+        path_expected.push(".expected");
+
+        let write = |path: &Path, contents: String| {
+            std::fs::write(path, contents)
+                .map_err(|e| Error::CannotWriteFile { msg: format!("{}: {e}", path.display()) })
+        };
+        write(path, self.grid.to_string())?;
+        write(Path::new(&path_expected), format!("xmas: {}\ncrosses: {}\n", self.xmas, self.crosses))
+    }
+}
+
+/// Hits found while generating, an "XMAS" by its start and direction and a cross by its centre
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Hits {
+    xmas: HashSet<(Point, Direction)>,
+    crosses: HashSet<Point>,
+}
+
+/// Partly filled grid that keeps track of the hits independently of the searches of the matrix
+struct Canvas {
+    cells: Grid<Option<char>>,
+}
+
+impl Canvas {
+    /// Attempts to find a free place for every planted word before giving up
+    const MAX_ATTEMPTS: usize = 10_000;
+    const MAS: [char; 3] = ['M', 'A', 'S'];
+
+    fn letter(&self, point: Point, step_row: isize, step_col: isize) -> Option<char> {
+        let point = point.offset(step_row, step_col)?;
+        self.cells.get(point).copied().flatten()
+    }
+
+    /// Whether "MAS" is spelled along a diagonal through the centre in either direction
+    fn is_mas(&self, centre: Point, (step_row, step_col): (isize, isize)) -> bool {
+        let ends = (self.letter(centre, -step_row, -step_col), self.letter(centre, step_row, step_col));
+        self.letter(centre, 0, 0) == Some(Self::MAS[1])
+            && (ends == (Some(Self::MAS[0]), Some(Self::MAS[2])) || ends == (Some(Self::MAS[2]), Some(Self::MAS[0])))
+    }
+
+    /// All the hits that contain one of the points
+    fn hits_through(&self, points: &[Point]) -> Hits {
+        let xmas: Vec<char> = XMAS.chars().collect();
+        let mut hits = Hits::default();
+        for &point in points {
+            for direction in Direction::ALL {
+                let (step_row, step_col) = direction.step();
+                for idx_letter in 0..xmas.len() as isize {
+                    let Some(start) = point.offset(-step_row * idx_letter, -step_col * idx_letter) else {
+                        continue;
+                    };
+                    let is_xmas = xmas.iter().enumerate().all(|(distance, &letter)| {
+                        let distance = distance as isize;
+                        self.letter(start, step_row * distance, step_col * distance) == Some(letter)
+                    });
+                    if is_xmas {
+                        hits.xmas.insert((start, direction));
+                    }
+                }
+            }
+
+            // A cross only covers its centre and the diagonal neighbours
+            for (step_row, step_col) in [(0, 0), (-1, -1), (-1, 1), (1, -1), (1, 1)] {
+                let Some(centre) = point.offset(step_row, step_col) else {
+                    continue;
+                };
+                if self.is_mas(centre, (1, 1)) && self.is_mas(centre, (1, -1)) {
+                    hits.crosses.insert(centre);
+                }
+            }
+        }
+        hits
+    }
+
+    /// Writes the letters into the free cells, unless a cell already has another letter.
+    /// Keeps them only if they create exactly the expected hits and returns whether they were kept.
+    fn plant(&mut self, letters: &[(Point, char)], expected: &Hits) -> bool {
+        if letters.iter().any(|&(point, letter)| self.cells.get(point).is_none_or(|cell| cell.is_some_and(|cell| cell != letter))) {
+            return false;
+        }
+
+        let new: Vec<Point> = letters.iter()
+            .filter(|&&(point, _)| self.cells[point].is_none())
+            .map(|&(point, _)| point)
+            .collect();
+        for &(point, letter) in letters {
+            self.cells[point] = Some(letter);
+        }
+        if &self.hits_through(&new) == expected {
+            return true;
+        }
+
+        for point in new {
+            self.cells[point] = None;
+        }
+        false
+    }
+}
+
+impl GeneratedPuzzle {
+    /// Generates a puzzle with exactly the planted "XMAS" and crosses, the noise creates no further ones
+    pub fn generate(spec: &PuzzleSpec) -> Result<Self> {
+        let error = |msg: &str| Error::CannotGeneratePuzzle { msg: msg.to_string() };
+        if spec.alphabet.is_empty() {
+            return Err(error("the alphabet is empty"));
+        }
+        if spec.xmas > 0 && spec.directions.is_empty() {
+            return Err(error("no directions for 'XMAS'"));
+        }
+
+        let mut rng = XorShiftRng::seed_from_u64(spec.seed);
+        let mut canvas = Canvas { cells: Grid::filled(spec.nrows, spec.ncols, None) };
+        let random_point = |rng: &mut XorShiftRng| {
+            Point::new(rng.random_range(0..spec.nrows), rng.random_range(0..spec.ncols))
+        };
+        let is_empty = spec.nrows == 0 || spec.ncols == 0;
+
+        for _ in 0..spec.crosses {
+            let is_planted = !is_empty && (0..Canvas::MAX_ATTEMPTS).any(|_| {
+                let centre = random_point(&mut rng);
+                let mut letters = vec![(centre, Canvas::MAS[1])];
+                for (step_row, step_col) in [(1, 1), (1, -1)] {
+                    let (first, last) = if rng.random_bool(0.5) { (0, 2) } else { (2, 0) };
+                    let (Some(upper), Some(lower)) = (centre.offset(-step_row, -step_col), centre.offset(step_row, step_col)) else {
+                        return false;
+                    };
+                    letters.push((upper, Canvas::MAS[first]));
+                    letters.push((lower, Canvas::MAS[last]));
+                }
+                let expected = Hits { crosses: HashSet::from([centre]), ..Hits::default() };
+                canvas.plant(&letters, &expected)
+            });
+            if !is_planted {
+                return Err(error("no place left for a cross"));
+            }
+        }
+
+        for _ in 0..spec.xmas {
+            let is_planted = !is_empty && (0..Canvas::MAX_ATTEMPTS).any(|_| {
+                let start = random_point(&mut rng);
+                let direction = *spec.directions.choose(&mut rng).expect("directions are not empty");
+                let (step_row, step_col) = direction.step();
+                let letters: Option<Vec<(Point, char)>> = XMAS.chars().enumerate()
+                    .map(|(distance, letter)| {
+                        let distance = distance as isize;
+                        Some((start.offset(step_row * distance, step_col * distance)?, letter))
+                    })
+                    .collect();
+                let expected = Hits { xmas: HashSet::from([(start, direction)]), ..Hits::default() };
+                letters.is_some_and(|letters| canvas.plant(&letters, &expected))
+            });
+            if !is_planted {
+                return Err(error("no place left for 'XMAS'"));
+            }
+        }
+
+        // Fill the rest in row-major order with noise letters that complete no hit
+        let mut alphabet = spec.alphabet.clone();
+        for point in canvas.cells.points() {
+            if canvas.cells[point].is_some() {
+                continue;
+            }
+            alphabet.shuffle(&mut rng);
+            let is_filled = alphabet.iter().any(|&letter| canvas.plant(&[(point, letter)], &Hits::default()));
+            if !is_filled {
+                return Err(error("every letter of the alphabet completes a hit"));
+            }
+        }
+
+        Ok(Self {
+            grid: canvas.cells.map(|cell| cell.expect("every cell is filled")),
+            xmas: spec.xmas,
+            crosses: spec.crosses,
+        })
+    }
+}
+
+/// Share of the cells that a planted "XMAS" starts at if no density is given
+pub const DEFAULT_XMAS_DENSITY: f64 = 0.025;
+
+/// Generates a square word search with `size` rows and columns, where a planted
+/// "XMAS" starts at the share `xmas_density` of the cells and a cross is centred
+/// at half as many
+pub fn generate_word_search(size: usize, xmas_density: f64, seed: u64) -> Result<SyntheticInput> {
+    let num_cells = (size * size) as f64;
+    let xmas = (num_cells * xmas_density) as usize;
+    let crosses = (num_cells * xmas_density / 2.0) as usize;
+    let spec = PuzzleSpec { nrows: size, ncols: size, xmas, crosses, seed, ..PuzzleSpec::default() };
+    let puzzle = GeneratedPuzzle::generate(&spec)?;
+    Ok(SyntheticInput {
+        input: puzzle.grid.to_string(),
+        answers: vec![puzzle.xmas as u64, puzzle.crosses as u64],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day4_ceres_search::{Matrix, X_MAS};
+
+    fn assert_answers(spec: &PuzzleSpec) {
+        let puzzle = GeneratedPuzzle::generate(spec).unwrap();
+        let matrix: Matrix = puzzle.grid.to_string().parse().unwrap();
+        assert_eq!(matrix.count_word(XMAS), puzzle.xmas, "{spec:?}");
+        assert_eq!(matrix.find_cross().len(), puzzle.crosses, "{spec:?}");
+        assert_eq!(matrix.count_template(X_MAS).unwrap(), puzzle.crosses, "{spec:?}");
+    }
+
+    #[test]
+    fn should_generate_known_answers() {
+        for seed in 0..20 {
+            assert_answers(&PuzzleSpec { nrows: 20, ncols: 30, xmas: 15, crosses: 8, seed, ..PuzzleSpec::default() });
+        }
+        assert_answers(&PuzzleSpec { alphabet: vec!['X', 'M', 'A', 'S', 'Q', 'ä'], seed: 7, ..PuzzleSpec::default() });
+        assert_answers(&PuzzleSpec { nrows: 1, ncols: 12, xmas: 2, crosses: 0, directions: vec![Direction::Left], ..PuzzleSpec::default() });
+        assert_answers(&PuzzleSpec { nrows: 0, ncols: 0, xmas: 0, crosses: 0, ..PuzzleSpec::default() });
+    }
+
+    #[test]
+    fn should_generate_word_search_of_size() {
+        let generated = generate_word_search(40, DEFAULT_XMAS_DENSITY, 5).unwrap();
+        let matrix: Matrix = generated.input.parse().unwrap();
+        assert_eq!(matrix.grid().nrows(), 40);
+        assert_eq!(generated.answers, vec![40, 20]);
+        assert_eq!(matrix.count_word(XMAS) as u64, generated.answers[0]);
+        assert_eq!(matrix.count_template(X_MAS).unwrap() as u64, generated.answers[1]);
+
+        let sparse = generate_word_search(40, 0.0, 5).unwrap();
+        assert_eq!(sparse.answers, vec![0, 0]);
+        let dense = generate_word_search(40, 0.05, 5).unwrap();
+        assert_eq!(dense.answers, vec![80, 40]);
+    }
+
+    #[test]
+    fn should_be_deterministic() {
+        let spec = PuzzleSpec { nrows: 10, ncols: 10, xmas: 3, crosses: 3, seed: 42, ..PuzzleSpec::default() };
+        let puzzle = GeneratedPuzzle::generate(&spec).unwrap();
+        assert_eq!(GeneratedPuzzle::generate(&spec).unwrap(), puzzle);
+        assert_ne!(GeneratedPuzzle::generate(&PuzzleSpec { seed: 43, ..spec }).unwrap(), puzzle);
+    }
+
+    #[test]
+    fn should_write_expected_answers() {
+        let spec = PuzzleSpec { nrows: 4, ncols: 4, xmas: 1, crosses: 1, seed: 3, ..PuzzleSpec::default() };
+        let puzzle = GeneratedPuzzle::generate(&spec).unwrap();
+        let path = std::env::temp_dir().join(format!("day4_generated_{}.txt", std::process::id()));
+        puzzle.write(&path).unwrap();
+
+        let path_expected = path.with_extension("txt.expected");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), puzzle.grid.to_string());
+        assert_eq!(std::fs::read_to_string(&path_expected).unwrap(), "xmas: 1\ncrosses: 1\n");
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(path_expected).unwrap();
+    }
+
+    #[test]
+    fn should_fail_without_room() {
+        assert!(GeneratedPuzzle::generate(&PuzzleSpec { nrows: 2, ncols: 2, xmas: 0, crosses: 1, ..PuzzleSpec::default() }).is_err());
+        assert!(GeneratedPuzzle::generate(&PuzzleSpec { nrows: 3, ncols: 3, xmas: 1, crosses: 0, ..PuzzleSpec::default() }).is_err());
+        assert!(GeneratedPuzzle::generate(&PuzzleSpec { alphabet: Vec::new(), ..PuzzleSpec::default() }).is_err());
+    }
+}
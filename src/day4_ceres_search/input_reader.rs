@@ -1,33 +1,37 @@
-use super::{FILENAME, XMASLetter, Matrix};
+use super::{FILENAME, Matrix};
+use crate::grid::{Addressing, Window};
 use crate::{Error, Result};
 
 
-pub(super) fn read_file() -> Result<Matrix> {
-    let file_str = std::fs::read_to_string(FILENAME)
-        .map_err(|e| Error::CannotReadFile { msg: e.to_string() })?;
+/// Part of the input that is searched and how words continue beyond its edges
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchArea {
+    /// The whole input if `None`
+    pub window: Option<Window>,
+    pub addressing: Addressing,
+}
 
-    // Parse input into a matrix where we only store the XMAS letters
-    // the input is given in the form of matrix already so we utilize this
 
-    let file_str = &file_str;
+/// Reads the input file where the letters are compared ignoring the case if `fold_case` is set
+fn read_file_with(fold_case: bool) -> Result<Matrix> {
+    let file_str = std::fs::read_to_string(FILENAME)
+        .map_err(|e| Error::CannotReadFile { msg: e.to_string() })?;
 
-    Ok(Matrix::from(file_str))
+    // The input is given in the form of a matrix already so we utilize this
+    Matrix::parse_with(&file_str, fold_case)
+}
 
-    // Initialize matrix
-    //let rows_str: Vec<&str> = file_str.trim().split('\n').collect();
-    //let len_rows: usize = rows_str.len();
-    //let len_cols: usize = rows_str[0].len();
-    //let mut matrix = Matrix::new(len_rows, len_cols);
-    //for (idx_x, line) in file_str.lines().enumerate() {
-        //for char in line.chars() {
-            //let val = XMASLetter::from(char);
-            //matrix.push_to_row(idx_x, val);
-        //}
-    //}
+/// Reads the input file and cuts out the area that is searched
+pub(super) fn read_area(area: SearchArea) -> Result<Matrix> {
+    read_area_with(area, false)
+}
 
-    // Check correctness on the num. of cols
-    //for row in &matrix.data {
-        //assert_eq!(row.len(), len_cols);
-    //}
-    //Ok(matrix)
+/// Reads the area of the input file where the letters are compared ignoring the case if `fold_case` is set
+pub(super) fn read_area_with(area: SearchArea, fold_case: bool) -> Result<Matrix> {
+    let matrix = read_file_with(fold_case)?;
+    let matrix = match area.window {
+        Some(window) => matrix.window(window)?,
+        None => matrix,
+    };
+    Ok(matrix.with_addressing(area.addressing))
 }
@@ -0,0 +1,52 @@
+use std::path::Path;
+use super::XMAS;
+use crate::grid::NdGrid;
+use crate::{Error, Result};
+
+
+/// Reads a word search of 2D layers separated by blank lines, see [`NdGrid::from_layers_with`],
+/// and counts "XMAS" in all the 3^N - 1 directions of its N dimensions
+pub fn xmas_search_layers(path: &Path) -> Result<usize> {
+    let input = std::fs::read_to_string(path)
+        .map_err(|e| Error::CannotReadFile { msg: format!("{}: {e}", path.display()) })?;
+    let grid = NdGrid::from_layers_with(&input, |letter| letter)?;
+    Ok(grid.count_word(&XMAS.chars().collect::<Vec<char>>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day4_ceres_search::{Matrix, XMAS};
+    use crate::grid::NdGrid;
+
+    #[test]
+    fn should_agree_with_matrix_in_2d() {
+        let data = "
+            MMMSXXMASM
+            MSAMXMSMSA
+            AMXSXMAAMM
+            MSAMASMSMX
+            XMASAMXAMM
+            XXAMMXXAMA
+            SMSMSASXSS
+            SAXAMASAAA
+            MAMMMXMMMM
+            MXMXAXMASX
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        let grid = NdGrid::from_layers_with(data, |letter| letter).unwrap();
+        assert_eq!(grid.directions().len(), 8);
+        for word in [XMAS, "MAS", "SAMX", "M", "MM", ""] {
+            let letters: Vec<char> = word.chars().collect();
+            assert_eq!(grid.count_word(&letters), matrix.count_word(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn should_search_single_layer_like_matrix() {
+        // One layer is a 3D cube whose extra directions leave it right away
+        let matrix: Matrix = "XMAS\nMMAA\nAXAS\nSAMX".parse().unwrap();
+        let cube = NdGrid::new(vec![1, 4, 4], matrix.grid().iter().map(|(_, &letter)| letter).collect()).unwrap();
+        assert_eq!(cube.directions().len(), 26);
+        assert_eq!(cube.count_word(&XMAS.chars().collect::<Vec<char>>()), matrix.count_word(XMAS));
+    }
+}
@@ -0,0 +1,96 @@
+use super::{read_area, CrossMatch, SearchArea, WordMatch, XMAS};
+use crate::{Error, Result};
+
+
+/// Format in which the found matches are dumped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchFormat {
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for MatchFormat {
+    type Err = Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format {
+            "json" => Ok(MatchFormat::Json),
+            "csv" => Ok(MatchFormat::Csv),
+            _ => Err(Error::InvalidArgument { arg: format.to_string() }),
+        }
+    }
+}
+
+/// Dumps the matches of both parts as
+///
+/// {"xmas": [{"row": 0, "col": 4, "direction": "right"}, ...],
+///  "cross": [{"row": 1, "col": 2, "main_diagonal": "down_right", "anti_diagonal": "up_right"}, ...]}
+fn to_json(xmas: &[WordMatch], cross: &[CrossMatch]) -> String {
+    let xmas: Vec<String> = xmas.iter()
+        .map(|m| format!(r#"    {{"row": {}, "col": {}, "direction": "{}"}}"#,
+            m.idx_row, m.idx_col, m.direction.name()))
+        .collect();
+    let cross: Vec<String> = cross.iter()
+        .map(|m| format!(r#"    {{"row": {}, "col": {}, "main_diagonal": "{}", "anti_diagonal": "{}"}}"#,
+            m.idx_row, m.idx_col, m.main_diagonal.name(), m.anti_diagonal.name()))
+        .collect();
+
+    format!("{{\n  \"xmas\": [\n{}\n  ],\n  \"cross\": [\n{}\n  ]\n}}\n", xmas.join(",\n"), cross.join(",\n"))
+}
+
+/// Dumps the matches of both parts as a single table, the columns
+/// that do not apply to a kind of match are left empty.
+fn to_csv(xmas: &[WordMatch], cross: &[CrossMatch]) -> String {
+    let mut csv = String::from("kind,row,col,direction,main_diagonal,anti_diagonal\n");
+    for m in xmas {
+        csv += &format!("xmas,{},{},{},,\n", m.idx_row, m.idx_col, m.direction.name());
+    }
+    for m in cross {
+        csv += &format!("cross,{},{},,{},{}\n", m.idx_row, m.idx_col, m.main_diagonal.name(), m.anti_diagonal.name());
+    }
+    csv
+}
+
+/// Reads the area of the input file and dumps where every "XMAS" starts and where every
+/// cross is centred. The positions are relative to the window if there is one.
+pub fn get_matches(area: SearchArea, format: MatchFormat) -> Result<String> {
+    let matrix = read_area(area)?;
+    let xmas = matrix.find_word(XMAS);
+    let cross = matrix.find_cross();
+    Ok(match format {
+        MatchFormat::Json => to_json(&xmas, &cross),
+        MatchFormat::Csv => to_csv(&xmas, &cross),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day4_ceres_search::Matrix;
+
+    #[test]
+    fn should_dump_matches() {
+        let matrix: Matrix = "
+            XMAS.
+            .MAS.
+            .MAS.
+        ".parse().unwrap();
+        let xmas = matrix.find_word(XMAS);
+        let cross = matrix.find_cross();
+
+        assert_eq!(to_csv(&xmas, &cross), "\
+kind,row,col,direction,main_diagonal,anti_diagonal
+xmas,0,0,right,,
+cross,1,2,,down_right,up_right
+");
+        assert_eq!(to_json(&xmas, &cross), r#"{
+  "xmas": [
+    {"row": 0, "col": 0, "direction": "right"}
+  ],
+  "cross": [
+    {"row": 1, "col": 2, "main_diagonal": "down_right", "anti_diagonal": "up_right"}
+  ]
+}
+"#);
+    }
+}
@@ -1,281 +1,451 @@
-use super::XMASLetter;
+use crate::grid::{Addressing, Grid, Point, Template, Window, WordSpace};
+use crate::{Error, Result};
 
-pub(super) type MatrixEntity = Option<XMASLetter>;
-pub(super) type Data = Vec<Vec<MatrixEntity>>;
 
+/// Letter grid of the word search, any Unicode chars are kept as they are
+/// or with their case folded, so that the search ignores the case.
+///
+/// Words and crosses either end at the edges or wrap around them, see [`Matrix::with_addressing`].
+/// Large matrices are searched in parallel bands of rows, see [`Matrix::with_threads`].
 #[derive(Debug)]
-pub(super) struct Matrix {
-    pub nrows: usize,
-    pub ncols: usize,
-    pub data: Data,
+pub struct Matrix {
+    grid: Grid<char>,
+    fold_case: bool,
+    addressing: Addressing,
+    threads: usize,
 }
 
-/// Implements matrix access and altering functions
+/// Implements matrix access functions
 impl Matrix {
-    pub fn new(nrows: usize, ncols: usize) -> Self {
-        let mut data = Vec::with_capacity(nrows);
-        for _ in 0..nrows {
-            data.push(Vec::with_capacity(ncols));
-        }
-        Self { nrows, ncols, data }
+    /// Parses the letters, if `fold_case` is set they are stored and later searched in lower case
+    pub fn parse_with(input: &str, fold_case: bool) -> Result<Self> {
+        let grid = Grid::from_str_with(input, |letter| normalize(letter, fold_case))?;
+        Ok(Self { grid, fold_case, addressing: Addressing::default(), threads: 1 })
     }
 
-    pub fn get(&self, idx_row: usize, idx_col: usize) -> &MatrixEntity {
-        if (idx_row + 1) > self.nrows || (idx_col + 1) > self.ncols { return &None }
-        self.data.get(idx_row).unwrap().get(idx_col).unwrap()
+    /// Matrix whose searches treat steps beyond the edges as given, e.g. wrapping around like on a torus
+    pub fn with_addressing(self, addressing: Addressing) -> Self {
+        Self { addressing, ..self }
     }
 
-    pub fn push_to_row(&mut self, idx_row: usize, val: Option<XMASLetter>) {
-        if idx_row >= self.nrows { panic!("out of bounds on Matrix::set") }
-        self.data[idx_row].push(val);
+    /// Matrix whose searches run on up to `threads` threads with identical results,
+    /// the default is a single thread and so is 0
+    pub fn with_threads(self, threads: usize) -> Self {
+        Self { threads, ..self }
     }
 
-}
+    pub fn addressing(&self) -> Addressing {
+        self.addressing
+    }
 
-/// Implements functions for checking the matching "XMAS" in the matrix
-impl Matrix {
-    /// Check if "SAMX" exists in the matrix at the given 'X' index
-    pub fn horizontal_backward(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_col < 3 {
-            false
-        } else {
-            let predicate_letter_m = self.get(idx_row, idx_col - 1).is_some_and(|c| c == XMASLetter::M);
-            let predicate_letter_a = self.get(idx_row, idx_col - 2).is_some_and(|c| c == XMASLetter::A);
-            let predicate_letter_s = self.get(idx_row, idx_col - 3).is_some_and(|c| c == XMASLetter::S);
-            predicate_letter_m && predicate_letter_a && predicate_letter_s
-        }
+    /// Copy of the letters inside the window that is searched the same way
+    pub fn window(&self, window: Window) -> Result<Self> {
+        Ok(Self { grid: self.grid.window(window)?, ..*self })
     }
 
-    /// Check if "XMAS" exists in the matrix at the given 'X' index
-    pub fn horizontal_forward(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_col > self.ncols - 4 {
-            false
-        } else {
-            let predicate_letter_m = self.get(idx_row, idx_col + 1).is_some_and(|c| c == XMASLetter::M);
-            let predicate_letter_a = self.get(idx_row, idx_col + 2).is_some_and(|c| c == XMASLetter::A);
-            let predicate_letter_s = self.get(idx_row, idx_col + 3).is_some_and(|c| c == XMASLetter::S);
-            predicate_letter_m && predicate_letter_a && predicate_letter_s
-        }
+    /// Letter as it is stored and compared
+    pub fn normalize(&self, letter: char) -> char {
+        normalize(letter, self.fold_case)
     }
 
-    /// Check if
-    /// """
-    /// X
-    /// M
-    /// A
-    /// S
-    /// """
-    /// exists in the matrix at the given 'X' index
-    pub fn vertical_forward(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_row > self.nrows - 4 {
-            false
-        } else {
-            let predicate_letter_m = self.get(idx_row + 1, idx_col).is_some_and(|c| c == XMASLetter::M);
-            let predicate_letter_a = self.get(idx_row + 2, idx_col).is_some_and(|c| c == XMASLetter::A);
-            let predicate_letter_s = self.get(idx_row + 3, idx_col).is_some_and(|c| c == XMASLetter::S);
-            predicate_letter_m && predicate_letter_a && predicate_letter_s
-        }
+    /// Letter at the given index, `None` outside of the matrix
+    pub fn get(&self, idx_row: usize, idx_col: usize) -> Option<char> {
+        self.grid.get((idx_row, idx_col)).copied()
     }
 
-    /// Check if
-    /// """
-    /// S
-    /// A
-    /// M
-    /// X
-    /// """
-    /// exists in the matrix at the given 'X' index
-    pub fn vertical_backward(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_row < 3 {
-            false
-        } else {
-            let predicate_letter_m = self.get(idx_row - 1, idx_col).is_some_and(|c| c == XMASLetter::M);
-            let predicate_letter_a = self.get(idx_row - 2, idx_col).is_some_and(|c| c == XMASLetter::A);
-            let predicate_letter_s = self.get(idx_row - 3, idx_col).is_some_and(|c| c == XMASLetter::S);
-            predicate_letter_m && predicate_letter_a && predicate_letter_s
-        }
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
     }
+}
 
-    /// Check if
-    /// """
-    /// X
-    ///  M
-    ///   A
-    ///    S
-    /// """
-    /// exists in the matrix at the given 'X' index
-    pub fn diagonal_forward_down(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_row > self.nrows - 4 || idx_col > self.ncols - 4 {
-            false
-        } else {
-            let predicate_letter_m = self.get(idx_row + 1, idx_col + 1).is_some_and(|c| c == XMASLetter::M);
-            let predicate_letter_a = self.get(idx_row + 2, idx_col + 2).is_some_and(|c| c == XMASLetter::A);
-            let predicate_letter_s = self.get(idx_row + 3, idx_col + 3).is_some_and(|c| c == XMASLetter::S);
-            predicate_letter_m && predicate_letter_a && predicate_letter_s
-        }
+/// Letter in lower case if the case is folded. Case folding is simple, so letters
+/// that turn into several chars in lower case, e.g. 'İ', are kept.
+fn normalize(letter: char, fold_case: bool) -> char {
+    if !fold_case {
+        return letter;
+    }
+    let mut lower = letter.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => letter,
     }
+}
+
+/// Direction in which a word is read in the matrix, ordered as in [`Direction::ALL`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Right, Left, Down, Up,
+    DownRight, UpLeft, DownLeft, UpRight,
+}
 
-    /// Check if
-    /// """
-    /// S
-    ///  A
-    ///   M
-    ///    X
-    /// """
-    /// exists in the matrix at the given 'X' index
-    pub fn diagonal_backward_up(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_row < 3 || idx_col < 3 {
-            false
-        } else {
-            let predicate_letter_m = self.get(idx_row - 1, idx_col - 1).is_some_and(|c| c == XMASLetter::M);
-            let predicate_letter_a = self.get(idx_row - 2, idx_col - 2).is_some_and(|c| c == XMASLetter::A);
-            let predicate_letter_s = self.get(idx_row - 3, idx_col - 3).is_some_and(|c| c == XMASLetter::S);
-            predicate_letter_m && predicate_letter_a && predicate_letter_s
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::Right, Direction::Left, Direction::Down, Direction::Up,
+        Direction::DownRight, Direction::UpLeft, Direction::DownLeft, Direction::UpRight,
+    ];
+
+    /// Name used when matches are dumped
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Right => "right",
+            Direction::Left => "left",
+            Direction::Down => "down",
+            Direction::Up => "up",
+            Direction::DownRight => "down_right",
+            Direction::UpLeft => "up_left",
+            Direction::DownLeft => "down_left",
+            Direction::UpRight => "up_right",
         }
     }
 
-    /// Check if
-    /// """
-    ///    X
-    ///   M
-    ///  A
-    /// S
-    /// """
-    /// exists in the matrix at the given 'X' index
-    pub fn diagonal_backward_down(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_row > self.nrows - 4 || idx_col < 3 {
-            false
-        } else {
-            let predicate_letter_m = self.get(idx_row + 1, idx_col - 1).is_some_and(|c| c == XMASLetter::M);
-            let predicate_letter_a = self.get(idx_row + 2, idx_col - 2).is_some_and(|c| c == XMASLetter::A);
-            let predicate_letter_s = self.get(idx_row + 3, idx_col - 3).is_some_and(|c| c == XMASLetter::S);
-            predicate_letter_m && predicate_letter_a && predicate_letter_s
+    /// Direction in which a word is read backwards
+    pub fn reversed(self) -> Direction {
+        match self {
+            Direction::Right => Direction::Left,
+            Direction::Left => Direction::Right,
+            Direction::Down => Direction::Up,
+            Direction::Up => Direction::Down,
+            Direction::DownRight => Direction::UpLeft,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::UpRight => Direction::DownLeft,
         }
     }
 
-    /// Check if
-    /// """
-    ///    S
-    ///   A
-    ///  M
-    /// X
-    /// """
-    /// exists in the matrix at the given 'X' index
-    pub fn diagonal_forward_up(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_row < 3 || idx_col > self.ncols - 4 {
-            false
-        } else {
-            let predicate_letter_m = self.get(idx_row - 1, idx_col + 1).is_some_and(|c| c == XMASLetter::M);
-            let predicate_letter_a = self.get(idx_row - 2, idx_col + 2).is_some_and(|c| c == XMASLetter::A);
-            let predicate_letter_s = self.get(idx_row - 3, idx_col + 3).is_some_and(|c| c == XMASLetter::S);
-            predicate_letter_m && predicate_letter_a && predicate_letter_s
+    /// (row, col) step to the next letter
+    pub fn step(self) -> (isize, isize) {
+        match self {
+            Direction::Right => (0, 1),
+            Direction::Left => (0, -1),
+            Direction::Down => (1, 0),
+            Direction::Up => (-1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::DownLeft => (1, -1),
+            Direction::UpRight => (-1, 1),
         }
     }
+}
 
-    /// Returns the number of total "xmas" matches at the given index of 'X'
-    pub fn count_xmas(&self, idx_row: usize, idx_col: usize) -> usize {
-        // Assume matrix is greater than 3x3 for simplicity, so we do not check that condition
-        let xmases = [
-            self.horizontal_forward(idx_row, idx_col), self.horizontal_backward(idx_row, idx_col),
-            self.vertical_forward(idx_row, idx_col), self.vertical_backward(idx_row, idx_col),
-            self.diagonal_forward_up(idx_row, idx_col), self.diagonal_forward_down(idx_row, idx_col),
-            self.diagonal_backward_up(idx_row, idx_col), self.diagonal_backward_down(idx_row, idx_col),
-        ];
-        let count_xmas_entries = xmases.iter().filter(|&&e| e).count();
-        count_xmas_entries
+/// A word found in the matrix starting at its first letter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WordMatch {
+    pub idx_row: usize,
+    pub idx_col: usize,
+    pub direction: Direction,
+}
+
+impl WordMatch {
+    /// Points of the letters of a word with the given length found in the matrix,
+    /// wrapped around its edges with toroidal addressing
+    pub fn points<'a>(&'a self, matrix: &'a Matrix, len: usize) -> impl Iterator<Item = Point> + 'a {
+        (0..len).filter_map(move |distance| {
+            let (idx_row, idx_col) = matrix.step(self.idx_row, self.idx_col, self.direction, distance)?;
+            Some(Point::new(idx_row, idx_col))
+        })
     }
 }
 
-/// Implements cross XMAS functions
+/// Two "MAS" crossing at their 'A' found in the matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrossMatch {
+    /// Index of the centre 'A'
+    pub idx_row: usize,
+    pub idx_col: usize,
+    /// Reading direction of "MAS" on the diagonal from the upper left to the bottom right
+    pub main_diagonal: Direction,
+    /// Reading direction of "MAS" on the diagonal from the upper right to the bottom left
+    pub anti_diagonal: Direction,
+}
+
+impl CrossMatch {
+    /// Points of the centre and the four corners of a cross found in the matrix,
+    /// wrapped around its edges with toroidal addressing
+    pub fn points<'a>(&'a self, matrix: &'a Matrix) -> impl Iterator<Item = Point> + 'a {
+        let corners = [Direction::UpLeft, Direction::UpRight, Direction::DownLeft, Direction::DownRight];
+        let centre = Point::new(self.idx_row, self.idx_col);
+        std::iter::once(centre).chain(corners.into_iter().filter_map(move |direction| {
+            let (idx_row, idx_col) = matrix.step(self.idx_row, self.idx_col, direction, 1)?;
+            Some(Point::new(idx_row, idx_col))
+        }))
+    }
+}
+
+/// Implements functions for searching words in the matrix
 impl Matrix {
-    fn upper_right_m_bottom_left_s(&self, idx_row: usize, idx_col: usize) -> bool {
-        if let Some(XMASLetter::M) = self.get(idx_row + 1, idx_col + 1) {
-            if let Some(XMASLetter::S) = self.get(idx_row - 1, idx_col - 1) {
-                true
-            } else { false }
-        } else { false }
+    /// Index that is `distance` steps away in the given direction, if it is inside the matrix
+    /// or, with toroidal addressing, after wrapping around its edges
+    fn step(&self, idx_row: usize, idx_col: usize, direction: Direction, distance: usize) -> Option<(usize, usize)> {
+        let (step_row, step_col) = direction.step();
+        let point = self.grid.step(
+            Point::new(idx_row, idx_col),
+            step_row * distance as isize,
+            step_col * distance as isize,
+            self.addressing,
+        )?;
+        Some((point.row, point.col))
+    }
+
+    /// Whether the letters are spelled starting at the given index in the given direction
+    pub fn is_word_at(&self, letters: &[char], idx_row: usize, idx_col: usize, direction: Direction) -> bool {
+        WordSpace::is_word_at(self, letters, &Point::new(idx_row, idx_col), &direction)
     }
 
-    fn upper_left_m_bottom_right_s(&self, idx_row: usize, idx_col: usize) -> bool {
-        if let Some(XMASLetter::M) = self.get(idx_row + 1, idx_col - 1) {
-            if let Some(XMASLetter::S) = self.get(idx_row - 1, idx_col + 1) {
-                true
-            } else { false }
-        } else { false }
+    /// Returns all the occurrences of the word in all eight directions.
+    /// A single letter word is only found once per cell.
+    pub fn find_word(&self, word: &str) -> Vec<WordMatch> {
+        let letters: Vec<char> = word.chars().map(|letter| self.normalize(letter)).collect();
+        self.grid.search_row_bands(self.threads, |rows| {
+            self.find_word_from(&letters, self.grid.points_in_rows(rows), &Direction::ALL)
+                .into_iter()
+                .map(|(point, direction)| WordMatch { idx_row: point.row, idx_col: point.col, direction })
+                .collect()
+        })
     }
 
-    fn upper_right_s_bottom_left_m(&self, idx_row: usize, idx_col: usize) -> bool {
-        if let Some(XMASLetter::M) = self.get(idx_row - 1, idx_col - 1) {
-            if let Some(XMASLetter::S) = self.get(idx_row + 1, idx_col + 1) {
-                true
-            } else { false }
-        } else { false }
+    /// Returns the number of occurrences of the word in all eight directions
+    pub fn count_word(&self, word: &str) -> usize {
+        self.find_word(word).len()
     }
+}
+
+/// The eight directions of the matrix, stepping with its addressing
+impl WordSpace for Matrix {
+    type Cell = char;
+    type Point = Point;
+    type Direction = Direction;
 
-    fn upper_left_s_bottom_right_m(&self, idx_row: usize, idx_col: usize) -> bool {
-        if let Some(XMASLetter::M) = self.get(idx_row - 1, idx_col + 1) {
-            if let Some(XMASLetter::S) = self.get(idx_row + 1, idx_col - 1) {
-                true
-            } else { false }
-        } else { false }
+    fn cell(&self, point: &Point) -> Option<&char> {
+        self.grid.get(*point)
     }
 
-    /// Whether a cross xmas pattern exists in the matrix such that e.g.
+    fn step(&self, start: &Point, direction: &Direction, distance: usize) -> Option<Point> {
+        let (idx_row, idx_col) = Matrix::step(self, start.row, start.col, *direction, distance)?;
+        Some(Point::new(idx_row, idx_col))
+    }
+}
+
+/// Implements cross XMAS functions
+impl Matrix {
+    const MAS: [char; 3] = ['M', 'A', 'S'];
+
+    /// Reading direction of "MAS" through the centre along one of the diagonals,
+    /// given as the direction that starts at the upper corner
+    fn mas_through(&self, idx_row: usize, idx_col: usize, downwards: Direction) -> Option<Direction> {
+        let mas = Self::MAS.map(|letter| self.normalize(letter));
+        [downwards, downwards.reversed()].into_iter().find(|&direction| {
+            // "MAS" starts one step before the centre
+            self.step(idx_row, idx_col, direction.reversed(), 1)
+                .is_some_and(|(start_row, start_col)| self.is_word_at(&mas, start_row, start_col, direction))
+        })
+    }
+
+    /// Returns the cross xmas pattern, if any, such that e.g.
     ///
     /// M.S
     /// .A.
     /// M.S
     ///
     /// is found in the matrix where A has the given indices idx_row and idx_col.
-    pub fn is_cross_xmas(&self, idx_row: usize, idx_col: usize) -> bool {
-        if idx_row == 0 || idx_row == self.nrows - 1 || idx_col == 0 || idx_col == self.ncols - 1 {
-            false
-        } else {
-            if (self.upper_right_m_bottom_left_s(idx_row, idx_col) && self.upper_left_m_bottom_right_s(idx_row, idx_col)) ||
-               (self.upper_right_m_bottom_left_s(idx_row, idx_col) && self.upper_left_s_bottom_right_m(idx_row, idx_col)) ||
-               (self.upper_right_s_bottom_left_m(idx_row, idx_col) && self.upper_left_s_bottom_right_m(idx_row, idx_col)) ||
-               (self.upper_right_s_bottom_left_m(idx_row, idx_col) && self.upper_left_m_bottom_right_s(idx_row, idx_col)) {
-                true
-            } else {
-                false
-            }
-        }
+    pub fn cross_at(&self, idx_row: usize, idx_col: usize) -> Option<CrossMatch> {
+        let main_diagonal = self.mas_through(idx_row, idx_col, Direction::DownRight)?;
+        let anti_diagonal = self.mas_through(idx_row, idx_col, Direction::DownLeft)?;
+        Some(CrossMatch { idx_row, idx_col, main_diagonal, anti_diagonal })
+    }
+
+    /// Returns the number of occurrences of the template, e.g. "M.S/.A./M.S",
+    /// in any of its rotations and reflections, where '.' matches any letter
+    pub fn count_template(&self, pattern: &str) -> Result<usize> {
+        let template = Template::parse_with(pattern, |letter| self.normalize(letter))?;
+        Ok(template.find_parallel(&self.grid, self.addressing, self.threads).len())
+    }
+
+    /// Returns all the crosses in row-major order of their centres
+    pub fn find_cross(&self) -> Vec<CrossMatch> {
+        self.grid.search_row_bands(self.threads, |rows| {
+            self.grid.points_in_rows(rows)
+                .filter_map(|point| self.cross_at(point.row, point.col))
+                .collect()
+        })
     }
 }
 
-impl From<&String> for Matrix {
-    fn from(input: &String) -> Self {
-        let data: Data = input
-            .trim()
-            .lines()
-            .map(|line| {
-                line.trim().chars()
-                    .map(|c| XMASLetter::from(c))
-                    .collect()
-            })
-            .collect();
-        let nrows = data.len();
-        let ncols = data.first().map_or(0, |row| row.len());
-
-        // We naively assert here, TODO: proper error handling
-        for row in &data {
-            assert_eq!(row.len(), ncols);
-        }
-        Self { nrows, ncols, data }
+impl std::str::FromStr for Matrix {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        Self::parse_with(input, false)
     }
 }
 
 impl std::fmt::Display for Matrix {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in &self.data {
-            for entry in row {
-                match entry {
-                    Some(letter) => write!(f, "{} ", letter)?,
-                    None => write!(f, ". ")?,
-                }
+        for row in self.grid.rows() {
+            for letter in row {
+                write!(f, "{letter} ")?;
             }
             writeln!(f)?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day4_ceres_search::{XMAS, X_MAS};
+    use crate::grid::NdGrid;
+    use proptest::prelude::*;
+
+    /// Naive reference that checks every cell and direction with signed indices,
+    /// which wrap around the edges with toroidal addressing
+    fn count_word_reference(letters: &[Vec<char>], word: &str, addressing: Addressing) -> usize {
+        let word: Vec<char> = word.chars().collect();
+        let directions: &[(i64, i64)] = match word.len() {
+            0 => &[],
+            1 => &[(0, 1)],
+            _ => &[(0, 1), (0, -1), (1, 0), (-1, 0), (1, 1), (-1, -1), (1, -1), (-1, 1)],
+        };
+        let at = |row: i64, col: i64| -> Option<char> {
+            let (row, col) = match addressing {
+                Addressing::Bounded => (row, col),
+                Addressing::Toroidal => {
+                    let nrows = letters.len() as i64;
+                    let ncols = letters.first()?.len() as i64;
+                    (row.rem_euclid(nrows), col.rem_euclid(ncols))
+                },
+            };
+            letters.get(usize::try_from(row).ok()?)?.get(usize::try_from(col).ok()?).copied()
+        };
+
+        let mut count = 0;
+        for (row, line) in letters.iter().enumerate() {
+            for col in 0..line.len() {
+                for &(step_row, step_col) in directions {
+                    let is_word = word.iter().enumerate().all(|(distance, &letter)| {
+                        let distance = distance as i64;
+                        at(row as i64 + step_row * distance, col as i64 + step_col * distance) == Some(letter)
+                    });
+                    count += usize::from(is_word);
+                }
+            }
+        }
+        count
+    }
+
+    /// Naive reference that reads both diagonals through every cell
+    fn count_cross_reference(letters: &[Vec<char>]) -> usize {
+        let mut count = 0;
+        for row in 1..letters.len().saturating_sub(1) {
+            for col in 1..letters[row].len().saturating_sub(1) {
+                let main: String = [letters[row - 1][col - 1], letters[row][col], letters[row + 1][col + 1]].iter().collect();
+                let anti: String = [letters[row - 1][col + 1], letters[row][col], letters[row + 1][col - 1]].iter().collect();
+                let is_mas = |diagonal: &str| diagonal == "MAS" || diagonal == "SAM";
+                count += usize::from(is_mas(&main) && is_mas(&anti));
+            }
+        }
+        count
+    }
+
+    /// Small grids including empty, 1xN and Nx1 ones, mostly made of the XMAS letters
+    fn letters() -> impl Strategy<Value = Vec<Vec<char>>> {
+        (1..6usize, 1..6usize).prop_flat_map(|(nrows, ncols)| {
+            let letter = prop::sample::select(vec!['X', 'M', 'A', 'S', 'X', 'M', 'A', 'S', 'x', '.']);
+            prop::collection::vec(prop::collection::vec(letter, ncols), 0..=nrows)
+        })
+    }
+
+    fn word() -> impl Strategy<Value = String> {
+        prop::collection::vec(prop::sample::select(vec!['X', 'M', 'A', 'S']), 0..5)
+            .prop_map(|letters| letters.into_iter().collect())
+    }
+
+    fn to_matrix(letters: &[Vec<char>]) -> Matrix {
+        let rows: Vec<String> = letters.iter().map(|row| row.iter().collect()).collect();
+        rows.join("\n").parse().unwrap()
+    }
+
+    #[test]
+    fn should_search_degenerate_grids() {
+        for data in ["", "X", "XMAS", "X\nM\nA\nS", "MS\nAA"] {
+            let matrix: Matrix = data.parse().unwrap();
+            assert!(matrix.count_word(XMAS) <= 1, "{data}");
+            assert_eq!(matrix.find_cross(), Vec::new(), "{data}");
+            assert_eq!(matrix.count_template(X_MAS).unwrap(), 0, "{data}");
+            assert_eq!(matrix.get(5, 5), None);
+        }
+    }
+
+    #[test]
+    fn should_wrap_points_of_matches_on_torus() {
+        let matrix: Matrix = "ASXM\n.S.S\n.M.M".parse().unwrap();
+        let matrix = matrix.with_addressing(Addressing::Toroidal);
+
+        let word_match = WordMatch { idx_row: 0, idx_col: 2, direction: Direction::Right };
+        assert!(matrix.find_word(XMAS).contains(&word_match));
+        let points: Vec<Point> = word_match.points(&matrix, XMAS.len()).collect();
+        assert_eq!(points, vec![Point::new(0, 2), Point::new(0, 3), Point::new(0, 0), Point::new(0, 1)]);
+
+        // Centred on the 'A' in the upper left corner with all its corners wrapped
+        let cross = matrix.cross_at(0, 0).unwrap();
+        let points: Vec<Point> = cross.points(&matrix).collect();
+        assert_eq!(points, vec![Point::new(0, 0), Point::new(2, 3), Point::new(2, 1), Point::new(1, 3), Point::new(1, 1)]);
+    }
+
+    #[test]
+    fn should_count_non_square_templates_in_every_orientation_at_a_point() {
+        let matrix: Matrix = "XMAS\nM...\nA...\nS...".parse().unwrap();
+        assert_eq!(matrix.count_word(XMAS), 2);
+        assert_eq!(matrix.count_template("XMAS").unwrap(), 2);
+        assert_eq!(matrix.count_template("SAMX").unwrap(), 2);
+    }
+
+    proptest! {
+        #[test]
+        fn should_count_words_like_reference(letters in letters(), word in word()) {
+            let matrix = to_matrix(&letters);
+            prop_assert_eq!(matrix.count_word(&word), count_word_reference(&letters, &word, Addressing::Bounded));
+            prop_assert_eq!(matrix.count_word(XMAS), count_word_reference(&letters, XMAS, Addressing::Bounded));
+        }
+
+        #[test]
+        fn should_count_words_on_torus_like_reference(letters in letters(), word in word()) {
+            let matrix = to_matrix(&letters).with_addressing(Addressing::Toroidal);
+            prop_assert_eq!(matrix.count_word(&word), count_word_reference(&letters, &word, Addressing::Toroidal));
+        }
+
+        #[test]
+        fn should_count_words_like_nd_grid(letters in letters(), word in word()) {
+            let rows: Vec<String> = letters.iter().map(|row| row.iter().collect()).collect();
+            let grid = NdGrid::from_layers_with(&rows.join("\n"), |letter| letter).unwrap();
+            let word_letters: Vec<char> = word.chars().collect();
+            prop_assert_eq!(to_matrix(&letters).count_word(&word), grid.count_word(&word_letters));
+        }
+
+        #[test]
+        fn should_count_crosses_like_reference(letters in letters()) {
+            let matrix = to_matrix(&letters);
+            let expected = count_cross_reference(&letters);
+            prop_assert_eq!(matrix.find_cross().len(), expected);
+            prop_assert_eq!(matrix.count_template(X_MAS).unwrap(), expected);
+        }
+
+        #[test]
+        fn should_search_in_parallel_like_sequential(letters in letters(), word in word(), threads in 2..8usize) {
+            let sequential = to_matrix(&letters);
+            let parallel = to_matrix(&letters).with_threads(threads);
+            prop_assert_eq!(parallel.find_word(&word), sequential.find_word(&word));
+            prop_assert_eq!(parallel.find_cross(), sequential.find_cross());
+            prop_assert_eq!(parallel.count_template(X_MAS).unwrap(), sequential.count_template(X_MAS).unwrap());
+        }
+
+        #[test]
+        fn should_find_dictionary_like_single_words(letters in letters(), words in prop::collection::vec(word(), 0..4)) {
+            let matrix = to_matrix(&letters);
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+            for found in matrix.find_words(&words).unwrap() {
+                prop_assert_eq!(found.matches, matrix.find_word(found.word));
+            }
+        }
+    }
+}
@@ -1,11 +1,25 @@
 mod input_reader;
 mod xmas_parser;
 mod matrix;
-mod xmas_letter;
+mod match_report;
+mod renderer;
+mod dictionary;
+mod generator;
+mod path_search;
+mod layer_search;
 
-use input_reader::read_file;
-use matrix::Matrix;
-use xmas_letter::XMASLetter;
+use input_reader::{read_area, read_area_with};
+pub use matrix::{Direction, Matrix, WordMatch, CrossMatch};
+pub use input_reader::SearchArea;
 pub use xmas_parser::{xmas_search, cross_search};
+pub use match_report::{MatchFormat, get_matches};
+pub use renderer::{Colouring, get_rendering};
+pub use dictionary::{DictionaryMatches, get_dictionary_matches};
+pub use path_search::{PathMatches, get_path_matches};
+pub use layer_search::xmas_search_layers;
+pub use generator::{PuzzleSpec, GeneratedPuzzle, generate_word_search, DEFAULT_XMAS_DENSITY};
 
 const FILENAME: &str = "data/day4_input.txt";
+const XMAS: &str = "XMAS";
+/// Two "MAS" crossing at their 'A', in any rotation or reflection
+const X_MAS: &str = "M.S/.A./M.S";
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use super::{read_area_with, Direction, Matrix, SearchArea};
+use crate::grid::{Grid, Point};
+use crate::Result;
+
+
+/// All the paths that spell one word of a dictionary
+#[derive(Debug, PartialEq, Eq)]
+pub struct PathMatches<'a> {
+    pub word: &'a str,
+    /// Points of the letters in the order of the word
+    pub paths: Vec<Vec<Point>>,
+}
+
+/// Prefix tree of the words, stored as a flat list of nodes where the root is the first one
+#[derive(Debug)]
+struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: HashMap<char, usize>,
+    /// Indices of the words that end at this node, several if a word is given twice
+    words: Vec<usize>,
+}
+
+impl Trie {
+    fn new<'a>(words: impl IntoIterator<Item = (usize, &'a [char])>) -> Self {
+        let mut trie = Self { nodes: vec![TrieNode::default()] };
+        for (idx_word, letters) in words {
+            let mut idx_node = 0;
+            for &letter in letters {
+                idx_node = match trie.nodes[idx_node].children.get(&letter) {
+                    Some(&idx_child) => idx_child,
+                    None => {
+                        trie.nodes.push(TrieNode::default());
+                        let idx_child = trie.nodes.len() - 1;
+                        trie.nodes[idx_node].children.insert(letter, idx_child);
+                        idx_child
+                    },
+                };
+            }
+            trie.nodes[idx_node].words.push(idx_word);
+        }
+        trie
+    }
+}
+
+/// State of the depth-first search through the matrix
+struct PathSearch<'m, 'w> {
+    matrix: &'m Matrix,
+    trie: Trie,
+    max_len: usize,
+    visited: Grid<bool>,
+    path: Vec<Point>,
+    found: Vec<PathMatches<'w>>,
+}
+
+impl PathSearch<'_, '_> {
+    /// Extends the path by the point if its letter continues a word of the trie
+    fn visit(&mut self, point: Point, idx_parent: usize) {
+        let letter = self.matrix.grid()[point];
+        let Some(&idx_node) = self.trie.nodes[idx_parent].children.get(&letter) else {
+            return;
+        };
+
+        self.visited[point] = true;
+        self.path.push(point);
+        for &idx_word in &self.trie.nodes[idx_node].words {
+            self.found[idx_word].paths.push(self.path.clone());
+        }
+
+        if self.path.len() < self.max_len {
+            // On a narrow torus several directions lead to the same neighbour
+            let mut neighbours: Vec<Point> = Direction::ALL.iter()
+                .filter_map(|direction| {
+                    let (step_row, step_col) = direction.step();
+                    self.matrix.grid().step(point, step_row, step_col, self.matrix.addressing())
+                })
+                .collect();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+
+            for next in neighbours {
+                if !self.visited[next] {
+                    self.visit(next, idx_node);
+                }
+            }
+        }
+
+        self.path.pop();
+        self.visited[point] = false;
+    }
+}
+
+/// Implements the search for words along paths that may turn at every letter
+impl Matrix {
+    /// Returns all the paths that spell one of the words, in the order of the given words.
+    /// A path steps to one of the eight neighbours at every letter and uses every cell at most once,
+    /// so a straight "XMAS" is one of the paths. Paths with more than `max_len` letters are not searched.
+    pub fn find_paths<'a>(&self, words: &[&'a str], max_len: Option<usize>) -> Vec<PathMatches<'a>> {
+        let letters: Vec<Vec<char>> = words.iter()
+            .map(|word| word.chars().map(|letter| self.normalize(letter)).collect())
+            .collect();
+        let trie = Trie::new(letters.iter().enumerate()
+            .filter(|(_, letters)| !letters.is_empty())
+            .map(|(idx_word, letters)| (idx_word, letters.as_slice())));
+
+        let mut search = PathSearch {
+            matrix: self,
+            trie,
+            max_len: max_len.unwrap_or(usize::MAX),
+            visited: self.grid().map(|_| false),
+            path: Vec::new(),
+            found: words.iter().map(|&word| PathMatches { word, paths: Vec::new() }).collect(),
+        };
+        if search.max_len > 0 {
+            for point in self.grid().points() {
+                search.visit(point, 0);
+            }
+        }
+        search.found
+    }
+}
+
+/// Reads the input file and reports for every word how many paths spell it and where they go, e.g.
+///
+/// XMAS: 1
+///   (0, 0) (0, 1) (1, 1) (1, 0)
+///
+/// Only the `area` is searched. If `fold_case` is set the case of the letters is ignored.
+pub fn get_path_matches(area: SearchArea, words: &[String], max_len: Option<usize>, fold_case: bool) -> Result<String> {
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let found = read_area_with(area, fold_case)?.find_paths(&words, max_len);
+
+    let mut report = String::new();
+    for PathMatches { word, paths } in found {
+        report += &format!("{word}: {}\n", paths.len());
+        for path in paths {
+            let points: Vec<String> = path.iter().map(|point| format!("({}, {})", point.row, point.col)).collect();
+            report += &format!("  {}\n", points.join(" "));
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day4_ceres_search::XMAS;
+    use crate::grid::Addressing;
+
+    fn count_paths(matrix: &Matrix, words: &[&str], max_len: Option<usize>) -> Vec<usize> {
+        matrix.find_paths(words, max_len).iter().map(|found| found.paths.len()).collect()
+    }
+
+    #[test]
+    fn should_find_turning_paths() {
+        let matrix: Matrix = "
+            XM
+            SA
+        ".parse().unwrap();
+        let found = matrix.find_paths(&[XMAS, "XAS", "SAX"], None);
+        assert_eq!(found[0].paths, vec![vec![Point::new(0, 0), Point::new(0, 1), Point::new(1, 1), Point::new(1, 0)]]);
+        assert_eq!(found[1].paths.len(), 1);
+        assert_eq!(found[2].paths, vec![vec![Point::new(1, 0), Point::new(1, 1), Point::new(0, 0)]]);
+        assert_eq!(count_paths(&matrix, &["", "MA", "MAM", "Q", "X"], None), vec![0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn should_not_reuse_cells() {
+        let matrix: Matrix = "ABA".parse().unwrap();
+        assert_eq!(count_paths(&matrix, &["ABA", "ABAB", "BAB", "AB", "AB"], None), vec![2, 0, 0, 2, 2]);
+    }
+
+    #[test]
+    fn should_limit_path_length() {
+        let matrix: Matrix = "
+            XMAS
+            MMAS
+        ".parse().unwrap();
+        assert_eq!(count_paths(&matrix, &[XMAS, "XM", "X"], Some(2)), vec![0, 3, 1]);
+        assert_eq!(count_paths(&matrix, &[XMAS, "XM", "X"], Some(0)), vec![0, 0, 0]);
+        assert_eq!(count_paths(&matrix, &[XMAS], Some(4)), count_paths(&matrix, &[XMAS], None));
+        assert!(count_paths(&matrix, &[XMAS], None)[0] >= matrix.count_word(XMAS));
+    }
+
+    #[test]
+    fn should_wrap_paths_on_a_torus() {
+        let matrix: Matrix = "AXB".parse().unwrap();
+        assert_eq!(count_paths(&matrix, &["BA"], None), vec![0]);
+        assert_eq!(count_paths(&matrix.with_addressing(Addressing::Toroidal), &["BA"], None), vec![1]);
+    }
+}
@@ -0,0 +1,139 @@
+use std::io::IsTerminal;
+use super::{read_area, CrossMatch, Direction, Matrix, SearchArea, WordMatch, XMAS};
+use crate::grid::Grid;
+use crate::{Error, Result};
+
+
+/// How the matched cells are coloured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colouring {
+    /// One colour per direction of "XMAS" and per orientation of the crosses
+    Direction,
+    /// Colours cycle from match to match
+    Match,
+}
+
+impl std::str::FromStr for Colouring {
+    type Err = Error;
+
+    fn from_str(colouring: &str) -> Result<Self> {
+        match colouring {
+            "direction" => Ok(Colouring::Direction),
+            "match" => Ok(Colouring::Match),
+            _ => Err(Error::InvalidArgument { arg: colouring.to_string() }),
+        }
+    }
+}
+
+/// Foreground colours of the matched cells
+const PALETTE: [&str; 8] = ["31", "32", "33", "34", "35", "36", "91", "94"];
+const DIM: &str = "2";
+
+/// Renders the matrix where the cells with a colour index are highlighted and
+/// all the others are dimmed. Without ANSI the cells that are not highlighted are '.'
+fn render(matrix: &Matrix, colours: &Grid<Option<usize>>, ansi: bool) -> String {
+    let mut rendering = String::new();
+    for (idx_row, row) in colours.rows().enumerate() {
+        for (idx_col, colour) in row.iter().enumerate() {
+            let letter = matrix.get(idx_row, idx_col).unwrap_or('.').to_string();
+            rendering += &match (colour, ansi) {
+                (Some(colour), true) => format!("\x1b[1;{}m{letter}\x1b[0m", PALETTE[colour % PALETTE.len()]),
+                (None, true) => format!("\x1b[{DIM}m{letter}\x1b[0m"),
+                (Some(_), false) => letter,
+                (None, false) => ".".to_string(),
+            };
+        }
+        rendering.push('\n');
+    }
+    rendering
+}
+
+/// Colour index of each cell of the "XMAS" matches, later matches win on shared cells
+fn colour_xmas(matrix: &Matrix, matches: &[WordMatch], colouring: Colouring) -> Grid<Option<usize>> {
+    let mut colours = matrix.grid().map(|_| None);
+    for (idx_match, word_match) in matches.iter().enumerate() {
+        let colour = match colouring {
+            Colouring::Direction => Direction::ALL.iter().position(|&d| d == word_match.direction).unwrap_or(0),
+            Colouring::Match => idx_match,
+        };
+        for point in word_match.points(matrix, XMAS.len()) {
+            colours[point] = Some(colour);
+        }
+    }
+    colours
+}
+
+/// Colour index of each cell of the cross matches, later matches win on shared cells
+fn colour_cross(matrix: &Matrix, matches: &[CrossMatch], colouring: Colouring) -> Grid<Option<usize>> {
+    let mut colours = matrix.grid().map(|_| None);
+    for (idx_match, cross_match) in matches.iter().enumerate() {
+        let colour = match colouring {
+            Colouring::Direction => {
+                let main = usize::from(cross_match.main_diagonal == Direction::UpLeft);
+                let anti = usize::from(cross_match.anti_diagonal == Direction::UpRight);
+                main * 2 + anti
+            },
+            Colouring::Match => idx_match,
+        };
+        for point in cross_match.points(matrix) {
+            colours[point] = Some(colour);
+        }
+    }
+    colours
+}
+
+/// Reads the area of the input file and renders the matches of both parts. Colours are
+/// only used if stdout is a terminal, otherwise the cells that do not match are '.'
+pub fn get_rendering(area: SearchArea, colouring: Colouring) -> Result<String> {
+    let matrix = read_area(area)?;
+    let ansi = std::io::stdout().is_terminal();
+
+    let xmas = colour_xmas(&matrix, &matrix.find_word(XMAS), colouring);
+    let cross = colour_cross(&matrix, &matrix.find_cross(), colouring);
+    Ok(format!("part 1:\n{}part 2:\n{}", render(&matrix, &xmas, ansi), render(&matrix, &cross, ansi)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Addressing;
+
+    #[test]
+    fn should_render_plain_text() {
+        let matrix: Matrix = "
+            XMASX
+            SAMXM
+            MSAMA
+        ".parse().unwrap();
+
+        let colours = colour_xmas(&matrix, &matrix.find_word(XMAS), Colouring::Direction);
+        assert_eq!(render(&matrix, &colours, false), "XMAS.\nSAMX.\n.....\n");
+
+        let matrix: Matrix = "
+            MXSX
+            XAXX
+            MXSX
+        ".parse().unwrap();
+        let colours = colour_cross(&matrix, &matrix.find_cross(), Colouring::Match);
+        assert_eq!(render(&matrix, &colours, false), "M.S.\n.A..\nM.S.\n");
+    }
+
+    #[test]
+    fn should_render_wrapped_matches_on_torus() {
+        let matrix: Matrix = "ASXM\n....".parse().unwrap();
+        let matrix = matrix.with_addressing(Addressing::Toroidal);
+        let colours = colour_xmas(&matrix, &matrix.find_word(XMAS), Colouring::Direction);
+        assert_eq!(render(&matrix, &colours, false), "ASXM\n....\n");
+    }
+
+    #[test]
+    fn should_render_ansi_colours() {
+        let matrix: Matrix = "XMAS.\nSAMX.".parse().unwrap();
+        let colours = colour_xmas(&matrix, &matrix.find_word(XMAS), Colouring::Direction);
+        let rendering = render(&matrix, &colours, true);
+
+        // Right is the first direction, left the second
+        assert!(rendering.starts_with("\x1b[1;31mX\x1b[0m\x1b[1;31mM\x1b[0m"));
+        assert!(rendering.contains("\x1b[2m.\x1b[0m\n\x1b[1;32mS\x1b[0m"));
+    }
+}
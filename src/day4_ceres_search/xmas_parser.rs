@@ -1,56 +1,45 @@
-use super::{XMASLetter, read_file, Matrix};
+use super::{read_area, SearchArea, XMAS, X_MAS};
 use crate::Result;
 
 
-pub fn xmas_search() -> Result<usize> {
-    let matrix: Matrix = read_file()?;
-    let mut count_xmas: usize = 0;
-    for idx_row in 0..matrix.nrows {
-        for idx_col in 0..matrix.ncols {
-            if let Some(XMASLetter::X) = matrix.get(idx_row, idx_col) {
-                count_xmas += matrix.count_xmas(idx_row, idx_col);
-            }
-        }
-    }
-    Ok(count_xmas)
+/// Counts "XMAS" in the area, searched on up to `threads` threads
+pub fn xmas_search(area: SearchArea, threads: usize) -> Result<usize> {
+    Ok(read_area(area)?.with_threads(threads).count_word(XMAS))
 }
 
-pub fn cross_search() -> Result<usize> {
-    let matrix: Matrix = read_file()?;
-    let mut count_cross: usize = 0;
-    for idx_row in 0..matrix.nrows {
-        for idx_col in 0..matrix.ncols {
-            if let Some(XMASLetter::A) = matrix.get(idx_row, idx_col) {
-                if matrix.is_cross_xmas(idx_row, idx_col) {
-                    count_cross += 1;
-                }
-            }
-        }
-    }
-    Ok(count_cross)
+/// Counts the crosses of "MAS" in the area, searched on up to `threads` threads
+pub fn cross_search(area: SearchArea, threads: usize) -> Result<usize> {
+    read_area(area)?.with_threads(threads).count_template(X_MAS)
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::day4_ceres_search::{Matrix, CrossMatch, WordMatch};
+    use crate::day4_ceres_search::matrix::Direction;
+    use crate::grid::Addressing;
+
+    fn has_xmas_at(matrix: &Matrix, idx_row: usize, idx_col: usize, direction: Direction) -> bool {
+        matrix.find_word(XMAS).contains(&WordMatch { idx_row, idx_col, direction })
+    }
 
     #[test]
     fn test_horizontal_forward() {
         let data = "
             XMAS
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        assert!(matrix.horizontal_forward(0, 0));
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert!(has_xmas_at(&matrix, 0, 0, Direction::Right));
     }
 
     #[test]
     fn test_horizontal_backward() {
         let data = "
             SAMX
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        assert!(matrix.horizontal_backward(0, 3));
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert!(has_xmas_at(&matrix, 0, 3, Direction::Left));
     }
 
     #[test]
@@ -60,9 +49,9 @@ mod tests {
             M
             A
             S
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        assert!(matrix.vertical_forward(0, 0));
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert!(has_xmas_at(&matrix, 0, 0, Direction::Down));
     }
 
     #[test]
@@ -72,9 +61,9 @@ mod tests {
             A
             M
             X
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        assert!(matrix.vertical_backward(3, 0));
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert!(has_xmas_at(&matrix, 3, 0, Direction::Up));
     }
 
     #[test]
@@ -84,9 +73,9 @@ mod tests {
             .M..
             ..A.
             ...S
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        assert!(matrix.diagonal_forward_down(0, 0));
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert!(has_xmas_at(&matrix, 0, 0, Direction::DownRight));
     }
 
     #[test]
@@ -96,9 +85,9 @@ mod tests {
             ..A.
             .M..
             X...
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        assert!(matrix.diagonal_forward_up(3, 0));
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert!(has_xmas_at(&matrix, 3, 0, Direction::UpRight));
     }
 
     #[test]
@@ -108,9 +97,9 @@ mod tests {
             .A..
             ..M.
             ...X
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        assert!(matrix.diagonal_backward_up(3, 3));
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert!(has_xmas_at(&matrix, 3, 3, Direction::UpLeft));
     }
 
     #[test]
@@ -120,9 +109,9 @@ mod tests {
             ..M.
             .A..
             S...
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        assert!(matrix.diagonal_backward_down(0, 3));
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert!(has_xmas_at(&matrix, 0, 3, Direction::DownLeft));
     }
 
     #[test]
@@ -138,17 +127,9 @@ mod tests {
             SAXAMASAAA
             MAMMMXMMMM
             MXMXAXMASX
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        let mut count_xmas: usize = 0;
-        for idx_row in 0..matrix.nrows {
-            for idx_col in 0..matrix.ncols {
-                if let Some(XMASLetter::X) = matrix.get(idx_row, idx_col) {
-                    count_xmas += matrix.count_xmas(idx_row, idx_col);
-                }
-            }
-        }
-        assert_eq!(count_xmas, 18);
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert_eq!(matrix.count_word(XMAS), 18);
     }
 
     #[test]
@@ -157,19 +138,11 @@ mod tests {
             M.S
             .A.
             M.S
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        let mut count_cross: usize = 0;
-        for idx_row in 0..matrix.nrows {
-            for idx_col in 0..matrix.ncols {
-                if let Some(XMASLetter::A) = matrix.get(idx_row, idx_col) {
-                    if matrix.is_cross_xmas(idx_row, idx_col) {
-                        count_cross += 1;
-                    }
-                }
-            }
-        }
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        let count_cross = matrix.find_cross().len();
         assert_eq!(count_cross, 1);
+        assert_eq!(matrix.count_template(X_MAS).unwrap(), 1);
     }
 
     #[test]
@@ -185,18 +158,69 @@ mod tests {
             .A.A.A.A..
             M.M.M.M.M.
             ..........
-        ".to_string();
-        let matrix = Matrix::from(&data);
-        let mut count_cross: usize = 0;
-        for idx_row in 0..matrix.nrows {
-            for idx_col in 0..matrix.ncols {
-                if let Some(XMASLetter::A) = matrix.get(idx_row, idx_col) {
-                    if matrix.is_cross_xmas(idx_row, idx_col) {
-                        count_cross += 1;
-                    }
-                }
-            }
-        }
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        let count_cross = matrix.find_cross().len();
         assert_eq!(count_cross, 9);
+        assert_eq!(matrix.count_template(X_MAS).unwrap(), 9);
+
+        let window = matrix.window("0,1,5,8".parse().unwrap()).unwrap();
+        assert_eq!(window.find_cross().len(), 5);
+        assert_eq!(window.count_template(X_MAS).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_count_word() {
+        let data = "
+            XMASAMX
+            MM.....
+            A.A....
+            S..S...
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert_eq!(matrix.count_word(XMAS), 4);
+        assert_eq!(matrix.count_word("SAMX"), 4);
+        assert_eq!(matrix.count_word("AM"), 6);
+        assert_eq!(matrix.count_word("X"), 2);
+        assert_eq!(matrix.count_word(""), 0);
+        assert_eq!(matrix.count_word("XMAZ"), 0);
+    }
+
+    #[test]
+    fn test_toroidal_search() {
+        let data = "
+            A....
+            .S..S
+            MAS.X
+            .....
+            .M..M
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert_eq!(matrix.count_word(XMAS), 0);
+        assert_eq!(matrix.find_cross().len(), 0);
+
+        // "XMAS" wraps from the right to the left edge, the cross around the corners
+        let torus = matrix.with_addressing(Addressing::Toroidal);
+        assert_eq!(torus.find_word(XMAS), vec![WordMatch { idx_row: 2, idx_col: 4, direction: Direction::Right }]);
+        assert_eq!(torus.count_word("SAMX"), 1);
+        assert_eq!(torus.find_cross().len(), 1);
+        assert_eq!(torus.cross_at(0, 0).map(|cross| cross.main_diagonal), Some(Direction::DownRight));
+        assert_eq!(torus.count_template(X_MAS).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_cross_orientation() {
+        let data = "
+            M.M
+            .A.
+            S.S
+        ";
+        let matrix: Matrix = data.parse().unwrap();
+        assert_eq!(matrix.find_cross(), vec![CrossMatch {
+            idx_row: 1,
+            idx_col: 1,
+            main_diagonal: Direction::DownRight,
+            anti_diagonal: Direction::DownLeft,
+        }]);
     }
 }
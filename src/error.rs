@@ -1,12 +1,47 @@
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub(super) enum Error {
+pub enum Error {
     #[error("Cannot read file: {msg}")]
     CannotReadFile {
         msg: String,
     },
 
+    #[error("Cannot write file: {msg}")]
+    CannotWriteFile {
+        msg: String,
+    },
+
     #[error("Cannot parse line")]
     CannotParseLine,
+
+    #[error("Cannot parse grid: {msg}")]
+    CannotParseGrid {
+        msg: String,
+    },
+
+    #[error("Out of bounds: {msg}")]
+    OutOfBounds {
+        msg: String,
+    },
+
+    #[error("Cannot generate puzzle: {msg}")]
+    CannotGeneratePuzzle {
+        msg: String,
+    },
+
+    #[error("Arithmetic overflow in the instruction at byte offset {offset}")]
+    ArithmeticOverflow {
+        offset: usize,
+    },
+
+    #[error("Cross-checks disagree: {msg}")]
+    CrossCheckMismatch {
+        msg: String,
+    },
+
+    #[error("Invalid argument: {arg}")]
+    InvalidArgument {
+        arg: String,
+    },
 }
@@ -0,0 +1,517 @@
+mod template;
+mod view;
+mod nd;
+mod word_search;
+
+use std::ops::{Index, IndexMut, Range};
+use std::str::FromStr;
+use crate::{Error, Result};
+
+pub use template::{Template, TemplateMatch};
+pub use view::{GridView, Transform};
+pub use nd::{NdGrid, NdWordMatch};
+pub use word_search::WordSpace;
+
+
+/// Position of a cell in a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// Point moved by the given (row, col) step, `None` if it would be negative
+    pub fn offset(self, step_row: isize, step_col: isize) -> Option<Self> {
+        Some(Self {
+            row: self.row.checked_add_signed(step_row)?,
+            col: self.col.checked_add_signed(step_col)?,
+        })
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row, col }
+    }
+}
+
+/// How steps beyond the edges of a grid are resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Addressing {
+    /// There are no cells beyond the edges
+    #[default]
+    Bounded,
+    /// The grid wraps around at its edges like a torus
+    Toroidal,
+}
+
+/// Rectangular region of a grid given by its upper left point and its size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    pub top_left: Point,
+    pub nrows: usize,
+    pub ncols: usize,
+}
+
+impl FromStr for Window {
+    type Err = Error;
+
+    /// Parses "row,col,nrows,ncols", e.g. "10,20,5,5"
+    fn from_str(window: &str) -> Result<Self> {
+        let values = window.split(',')
+            .map(|value| value.trim().parse::<usize>())
+            .collect::<std::result::Result<Vec<usize>, _>>()
+            .map_err(|e| Error::InvalidArgument { arg: format!("window {window}: {e}") })?;
+        match values[..] {
+            [row, col, nrows, ncols] => Ok(Self { top_left: Point::new(row, col), nrows, ncols }),
+            _ => Err(Error::InvalidArgument { arg: format!("window {window} is not row,col,nrows,ncols") }),
+        }
+    }
+}
+
+/// Two dimensional grid of cells stored flat in row-major order.
+///
+/// Cells are accessed checked with [`Grid::get`] or unchecked with `grid[(row, col)]`,
+/// which panics out of bounds like slices do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    nrows: usize,
+    ncols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Steps to the 4-connected neighbours: up, left, right, down
+    const STEPS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+    /// Steps to the 8-connected neighbours in row-major order
+    const STEPS_8: [(isize, isize); 8] = [
+        (-1, -1), (-1, 0), (-1, 1),
+        (0, -1), (0, 1),
+        (1, -1), (1, 0), (1, 1),
+    ];
+
+    /// Creates a grid from the cells in row-major order
+    pub fn new(nrows: usize, ncols: usize, cells: Vec<T>) -> Result<Self> {
+        if nrows.checked_mul(ncols) != Some(cells.len()) {
+            return Err(Error::CannotParseGrid {
+                msg: format!("{} cells do not fit into {nrows}x{ncols}", cells.len()),
+            });
+        }
+        Ok(Self { nrows, ncols, cells })
+    }
+
+    /// Creates a grid with every cell set to the value
+    pub fn filled(nrows: usize, ncols: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self { nrows, ncols, cells: vec![value; nrows * ncols] }
+    }
+
+    /// Parses a grid where every char is mapped to a cell. Surrounding whitespace
+    /// of the input and of each line is ignored, all lines need the same length.
+    pub fn from_str_with(input: &str, mut to_cell: impl FnMut(char) -> T) -> Result<Self> {
+        let mut nrows: usize = 0;
+        let mut ncols: usize = 0;
+        let mut cells = Vec::new();
+
+        for line in input.trim().lines() {
+            let len_before = cells.len();
+            cells.extend(line.trim().chars().map(&mut to_cell));
+            let len_row = cells.len() - len_before;
+
+            if nrows == 0 {
+                ncols = len_row;
+            } else if len_row != ncols {
+                return Err(Error::CannotParseGrid {
+                    msg: format!("row {nrows} has {len_row} cells instead of {ncols}"),
+                });
+            }
+            nrows += 1;
+        }
+
+        Ok(Self { nrows, ncols, cells })
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whether the point is inside the grid
+    pub fn contains(&self, point: Point) -> bool {
+        point.row < self.nrows && point.col < self.ncols
+    }
+
+    fn idx(&self, point: Point) -> Option<usize> {
+        self.contains(point).then_some(point.row * self.ncols + point.col)
+    }
+
+    pub fn get(&self, point: impl Into<Point>) -> Option<&T> {
+        let idx = self.idx(point.into())?;
+        self.cells.get(idx)
+    }
+
+    pub fn get_mut(&mut self, point: impl Into<Point>) -> Option<&mut T> {
+        let idx = self.idx(point.into())?;
+        self.cells.get_mut(idx)
+    }
+
+    /// Iterates the rows as slices from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.nrows).map(|row| &self.cells[(row * self.ncols)..((row + 1) * self.ncols)])
+    }
+
+    /// Iterates the cells of a column from top to bottom,
+    /// empty if the column is out of bounds
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        let nrows = if col < self.ncols { self.nrows } else { 0 };
+        (0..nrows).map(move |row| &self.cells[row * self.ncols + col])
+    }
+
+    /// Iterates the columns from left to right
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.ncols).map(|col| self.column(col))
+    }
+
+    /// Iterates all the points in row-major order
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        self.points_in_rows(0..self.nrows)
+    }
+
+    /// Iterates the points of the given rows in row-major order, rows outside of the grid are skipped
+    pub fn points_in_rows(&self, rows: Range<usize>) -> impl Iterator<Item = Point> {
+        let ncols = self.ncols;
+        let rows = rows.start.min(self.nrows)..rows.end.min(self.nrows);
+        rows.flat_map(move |row| (0..ncols).map(move |col| Point { row, col }))
+    }
+
+    /// Runs the search for bands of consecutive rows on up to `threads` threads and concatenates
+    /// the results in the order of the bands. A search only looks for results that start in its
+    /// band but reads the whole grid, so results that overlap into the neighbouring bands are kept.
+    /// With a single thread the search runs on the current thread for all rows. There are never
+    /// more threads than rows or than the available parallelism of the machine.
+    pub fn search_row_bands<R: Send>(&self, threads: usize, search: impl Fn(Range<usize>) -> Vec<R> + Sync) -> Vec<R> {
+        let max_threads = std::thread::available_parallelism().map_or(1, usize::from);
+        let threads = threads.min(self.nrows).min(max_threads);
+        if threads <= 1 {
+            return search(0..self.nrows);
+        }
+
+        let nrows = self.nrows;
+        let len_band = nrows.div_ceil(threads);
+        let search = &search;
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..nrows)
+                .step_by(len_band)
+                .map(|start| scope.spawn(move || search(start..(start + len_band).min(nrows))))
+                .collect();
+            handles.into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                .collect()
+        })
+    }
+
+    /// Iterates all the cells with their points in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// Iterates the up to 4 neighbours sharing an edge with the point
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &Self::STEPS_4)
+    }
+
+    /// Iterates the up to 8 neighbours sharing an edge or a corner with the point
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &Self::STEPS_8)
+    }
+
+    fn neighbours<'a>(&'a self, point: Point, steps: &'static [(isize, isize)]) -> impl Iterator<Item = Point> + 'a {
+        steps.iter()
+            .filter_map(move |&(step_row, step_col)| point.offset(step_row, step_col))
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    /// Point moved by the given (row, col) step, `None` if it is not inside the grid.
+    /// With toroidal addressing every step from a point inside the grid stays inside.
+    pub fn step(&self, point: Point, step_row: isize, step_col: isize, addressing: Addressing) -> Option<Point> {
+        match addressing {
+            Addressing::Bounded => point.offset(step_row, step_col).filter(|&point| self.contains(point)),
+            Addressing::Toroidal => {
+                if !self.contains(point) {
+                    return None;
+                }
+                let wrap = |idx: usize, step: isize, len: usize| {
+                    // Both fit into i128 without overflow, the result is below `len`
+                    (idx as i128 + step as i128).rem_euclid(len as i128) as usize
+                };
+                Some(Point::new(wrap(point.row, step_row, self.nrows), wrap(point.col, step_col, self.ncols)))
+            },
+        }
+    }
+
+    /// Borrowed view of the cells inside the window, which has to fit into the grid
+    pub fn view_window(&self, window: Window) -> Result<GridView<'_, T>> {
+        let Window { top_left, nrows, ncols } = window;
+        let fits = |start: usize, len: usize, len_grid: usize| start.checked_add(len).is_some_and(|end| end <= len_grid);
+        if !fits(top_left.row, nrows, self.nrows) || !fits(top_left.col, ncols, self.ncols) {
+            return Err(Error::OutOfBounds {
+                msg: format!("{nrows}x{ncols} window at {top_left:?} does not fit into the {}x{} grid", self.nrows, self.ncols),
+            });
+        }
+        Ok(GridView::new(self, window, Transform::Identity))
+    }
+
+    /// Owned copy of the cells inside the window, which has to fit into the grid
+    pub fn window(&self, window: Window) -> Result<Self>
+    where
+        T: Clone,
+    {
+        Ok(self.view_window(window)?.to_grid())
+    }
+
+    /// Iterates the points from the start in (row, col) steps until the line leaves the grid,
+    /// a zero step only yields the start
+    pub fn line(&self, start: Point, step_row: isize, step_col: isize) -> impl Iterator<Item = Point> + '_ {
+        let is_zero = (step_row, step_col) == (0, 0);
+        std::iter::successors(Some(start).filter(|&start| self.contains(start)), move |point| {
+            if is_zero {
+                return None;
+            }
+            point.offset(step_row, step_col).filter(|&next| self.contains(next))
+        })
+    }
+
+    /// Iterates all the lines in (row, col) steps that cover the grid, each one starting
+    /// at the edge where it enters the grid. The lines are ordered by their starts in row-major order.
+    pub fn lines(&self, step_row: isize, step_col: isize) -> impl Iterator<Item = impl Iterator<Item = Point> + '_> + '_ {
+        self.points()
+            .filter(move |point| {
+                // A zero step never leaves the grid, so there are no lines
+                let is_zero = (step_row, step_col) == (0, 0);
+                let previous = point.offset(-step_row, -step_col);
+                !is_zero && previous.is_none_or(|previous| !self.contains(previous))
+            })
+            .map(move |start| self.line(start, step_row, step_col))
+    }
+
+    /// Iterates the diagonals from the upper left to the bottom right, the ones
+    /// starting in the top row from left to right first
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        self.lines(1, 1).map(|line| line.map(|point| &self[point]))
+    }
+
+    /// Iterates the diagonals from the upper right to the bottom left, the ones
+    /// starting in the top row from left to right first
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        self.lines(1, -1).map(|line| line.map(|point| &self[point]))
+    }
+
+    /// View of the rotated or mirrored grid that does not copy the cells
+    pub fn view(&self, transform: Transform) -> GridView<'_, T> {
+        let window = Window { top_left: Point::new(0, 0), nrows: self.nrows, ncols: self.ncols };
+        GridView::new(self, window, transform)
+    }
+
+    /// Owned copy that is rotated or mirrored
+    pub fn transformed(&self, transform: Transform) -> Self
+    where
+        T: Clone,
+    {
+        self.view(transform).to_grid()
+    }
+
+    /// Owned copy mirrored along the diagonal from the upper left to the bottom right
+    pub fn transposed(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::Transpose)
+    }
+
+    /// Owned copy rotated by 90 degrees clockwise
+    pub fn rotated_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::Rotate90)
+    }
+
+    /// Owned copy mirrored left to right
+    pub fn flipped_horizontally(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::FlipHorizontal)
+    }
+
+    /// Owned copy mirrored top to bottom
+    pub fn flipped_vertically(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::FlipVertical)
+    }
+
+    /// Creates a grid of the same shape with every cell mapped
+    pub fn map<U>(&self, to_cell: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { nrows: self.nrows, ncols: self.ncols, cells: self.cells.iter().map(to_cell).collect() }
+    }
+}
+
+impl<T, P: Into<Point>> Index<P> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: P) -> &T {
+        let point = point.into();
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is out of bounds of the {}x{} grid", self.nrows, self.ncols))
+    }
+}
+
+impl<T, P: Into<Point>> IndexMut<P> for Grid<T> {
+    fn index_mut(&mut self, point: P) -> &mut T {
+        let point = point.into();
+        let (nrows, ncols) = (self.nrows, self.ncols);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is out of bounds of the {nrows}x{ncols} grid"))
+    }
+}
+
+impl<T: From<char>> FromStr for Grid<T> {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        Self::from_str_with(input, T::from)
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_and_display() {
+        let grid: Grid<char> = "
+            abc
+            def
+        ".parse().unwrap();
+        assert_eq!((grid.nrows(), grid.ncols()), (2, 3));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+
+        let digits = Grid::from_str_with("12\n34", |c| c.to_digit(10).unwrap()).unwrap();
+        assert_eq!(digits.rows().map(|row| row.iter().sum::<u32>()).collect::<Vec<_>>(), vec![3, 7]);
+        assert_eq!(digits.columns().map(|col| col.sum::<u32>()).collect::<Vec<_>>(), vec![4, 6]);
+
+        assert!("ab\nc".parse::<Grid<char>>().is_err());
+        assert!("".parse::<Grid<char>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_iterate_neighbours() {
+        let grid = Grid::filled(3, 3, 0);
+        assert_eq!(grid.neighbours4(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbours4(Point::new(0, 0)).collect::<Vec<_>>(),
+            vec![Point::new(0, 1), Point::new(1, 0)],
+        );
+        assert_eq!(grid.neighbours8(Point::new(2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn should_index_mutably() {
+        let mut grid = Grid::new(2, 2, vec![1, 2, 3, 4]).unwrap();
+        grid[(0, 1)] = 5;
+        *grid.get_mut(Point::new(1, 0)).unwrap() += 1;
+        assert_eq!(grid.iter().map(|(_, &cell)| cell).collect::<Vec<_>>(), vec![1, 5, 4, 4]);
+        assert!(Grid::new(2, 2, vec![1]).is_err());
+    }
+
+    #[test]
+    fn should_iterate_lines() {
+        let grid: Grid<char> = "
+            abc
+            def
+        ".parse().unwrap();
+        let collect = |lines: Vec<String>| lines.join(" ");
+        assert_eq!(collect(grid.diagonals().map(|line| line.collect()).collect()), "ae bf c d");
+        assert_eq!(collect(grid.anti_diagonals().map(|line| line.collect()).collect()), "a bd ce f");
+        assert_eq!(grid.lines(0, -1).count(), 2);
+        assert_eq!(grid.lines(0, 0).count(), 0);
+        assert_eq!(grid.line(Point::new(1, 2), 0, 0).collect::<Vec<_>>(), vec![Point::new(1, 2)]);
+        assert_eq!(grid.line(Point::new(2, 0), 0, 1).count(), 0);
+        assert_eq!(grid.transposed().flipped_vertically(), grid.transformed(Transform::Rotate270));
+    }
+
+    #[test]
+    fn should_search_row_bands() {
+        let grid = Grid::new(5, 2, (0..10).collect()).unwrap();
+        let search = |rows: Range<usize>| grid.points_in_rows(rows).map(|point| grid[point]).collect::<Vec<_>>();
+        for threads in [0, 1, 2, 3, 5, 8, 100_000] {
+            assert_eq!(grid.search_row_bands(threads, search), (0..10).collect::<Vec<_>>(), "{threads} threads");
+        }
+        assert_eq!(grid.points_in_rows(4..9).count(), 2);
+
+        // At most one band per row however many threads are asked for
+        let bands = grid.search_row_bands(usize::MAX, |rows| vec![rows]);
+        assert!(!bands.is_empty() && bands.len() <= grid.nrows(), "{bands:?}");
+    }
+
+    #[test]
+    fn should_step_bounded_and_toroidal() {
+        let grid = Grid::filled(2, 3, 0);
+        let corner = Point::new(0, 2);
+        assert_eq!(grid.step(corner, -1, 1, Addressing::Bounded), None);
+        assert_eq!(grid.step(corner, 1, -1, Addressing::Bounded), Some(Point::new(1, 1)));
+        assert_eq!(grid.step(corner, -1, 1, Addressing::Toroidal), Some(Point::new(1, 0)));
+        assert_eq!(grid.step(corner, 5, -7, Addressing::Toroidal), Some(Point::new(1, 1)));
+        assert_eq!(grid.step(Point::new(2, 0), 0, 1, Addressing::Toroidal), None);
+        assert_eq!(Grid::filled(0, 0, 0).step(Point::new(0, 0), 0, 0, Addressing::Toroidal), None);
+    }
+
+    #[test]
+    fn should_copy_windows() {
+        let grid: Grid<char> = "
+            abcd
+            efgh
+            ijkl
+        ".parse().unwrap();
+        let window = grid.window("1,1,2,3".parse().unwrap()).unwrap();
+        assert_eq!(window.to_string(), "fgh
+jkl
+");
+        assert!(grid.window("0,0,0,0".parse().unwrap()).unwrap().is_empty());
+        assert!(grid.window("2,1,2,1".parse().unwrap()).is_err());
+        assert!(grid.window(Window { top_left: Point::new(0, usize::MAX), nrows: 1, ncols: 2 }).is_err());
+        assert!("1,2,3".parse::<Window>().is_err());
+        assert!("1,2,3,x".parse::<Window>().is_err());
+    }
+}
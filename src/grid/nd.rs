@@ -0,0 +1,242 @@
+use super::WordSpace;
+use crate::{Error, Result};
+
+
+/// Grid with any number of dimensions stored flat with the last axis changing fastest,
+/// e.g. a 3D cube has the shape [layers, rows, columns].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdGrid<T> {
+    shape: Vec<usize>,
+    cells: Vec<T>,
+}
+
+/// A word found in an N-D grid starting at its first letter
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NdWordMatch {
+    pub start: Vec<usize>,
+    /// Step per axis, each one of -1, 0 and 1
+    pub direction: Vec<isize>,
+}
+
+impl<T> NdGrid<T> {
+    /// Creates a grid from the cells with the last axis changing fastest
+    pub fn new(shape: Vec<usize>, cells: Vec<T>) -> Result<Self> {
+        let len = shape.iter().try_fold(1usize, |len, &len_axis| len.checked_mul(len_axis));
+        if len != Some(cells.len()) {
+            return Err(Error::CannotParseGrid {
+                msg: format!("{} cells do not fit into the shape {shape:?}", cells.len()),
+            });
+        }
+        Ok(Self { shape, cells })
+    }
+
+    /// Parses 2D layers of chars where every char is mapped to a cell. Layers are separated by
+    /// a blank line, which adds a third dimension. Two blank lines separate blocks of layers
+    /// for a fourth dimension and so on. All the rows, layers and blocks need the same size.
+    pub fn from_layers_with(input: &str, mut to_cell: impl FnMut(char) -> T) -> Result<Self> {
+        // Every non-blank line with the number of blank lines in front of it
+        let mut lines: Vec<(usize, &str)> = Vec::new();
+        let mut len_gap: usize = 0;
+        for line in input.trim().lines().map(str::trim) {
+            if line.is_empty() {
+                len_gap += 1;
+            } else {
+                lines.push((len_gap, line));
+                len_gap = 0;
+            }
+        }
+
+        let depth = lines.iter().map(|&(len_gap, _)| len_gap).max().unwrap_or(0);
+        let mut cells = Vec::new();
+        let shape = Self::parse_block(&lines, depth, &mut cells, &mut to_cell)?;
+        Ok(Self { shape, cells })
+    }
+
+    /// Parses the lines of a block that is split by gaps of `depth` blank lines and returns its shape
+    fn parse_block(lines: &[(usize, &str)], depth: usize, cells: &mut Vec<T>, to_cell: &mut impl FnMut(char) -> T) -> Result<Vec<usize>> {
+        if depth == 0 {
+            let ncols = lines.first().map_or(0, |(_, line)| line.chars().count());
+            for (idx_row, (_, line)) in lines.iter().enumerate() {
+                let len_row = line.chars().count();
+                if len_row != ncols {
+                    return Err(Error::CannotParseGrid { msg: format!("row {idx_row} has {len_row} cells instead of {ncols}") });
+                }
+                cells.extend(line.chars().map(&mut *to_cell));
+            }
+            return Ok(vec![lines.len(), ncols]);
+        }
+
+        let mut shape: Option<Vec<usize>> = None;
+        let mut nblocks: usize = 0;
+        // The first line of a block has the gap in front of it, except for the very first one
+        let starts: Vec<usize> = (0..lines.len()).filter(|&idx| idx == 0 || lines[idx].0 == depth).collect();
+        for (idx_block, &start) in starts.iter().enumerate() {
+            let end = starts.get(idx_block + 1).copied().unwrap_or(lines.len());
+            let block_shape = Self::parse_block(&lines[start..end], depth - 1, cells, to_cell)?;
+            match &shape {
+                Some(shape) if shape != &block_shape => {
+                    return Err(Error::CannotParseGrid {
+                        msg: format!("block {idx_block} has the shape {block_shape:?} instead of {shape:?}"),
+                    });
+                },
+                _ => shape = Some(block_shape),
+            }
+            nblocks += 1;
+        }
+
+        let mut shape = shape.unwrap_or_else(|| vec![0; depth + 1]);
+        shape.insert(0, nblocks);
+        Ok(shape)
+    }
+
+    /// Lengths of the axes, the outermost first
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Index into the flat cells, `None` if the point is outside of the grid
+    fn idx(&self, point: &[usize]) -> Option<usize> {
+        if point.len() != self.shape.len() {
+            return None;
+        }
+        point.iter().zip(&self.shape).try_fold(0, |idx, (&coordinate, &len_axis)| {
+            (coordinate < len_axis).then_some(idx * len_axis + coordinate)
+        })
+    }
+
+    pub fn get(&self, point: &[usize]) -> Option<&T> {
+        self.cells.get(self.idx(point)?)
+    }
+
+    /// Point of the flat index, the last axis changes fastest
+    fn point(&self, mut idx: usize) -> Vec<usize> {
+        let mut point = vec![0; self.shape.len()];
+        for (coordinate, &len_axis) in point.iter_mut().zip(&self.shape).rev() {
+            *coordinate = idx % len_axis;
+            idx /= len_axis;
+        }
+        point
+    }
+
+    /// All the 3^N - 1 steps to the neighbours sharing at least a corner, ordered
+    /// lexicographically from (-1, -1, ..) to (1, 1, ..)
+    pub fn directions(&self) -> Vec<Vec<isize>> {
+        let mut directions: Vec<Vec<isize>> = vec![Vec::new()];
+        for _ in &self.shape {
+            directions = directions.into_iter()
+                .flat_map(|direction| [-1, 0, 1].map(|step| [direction.as_slice(), &[step]].concat()))
+                .collect();
+        }
+        directions.retain(|direction| direction.iter().any(|&step| step != 0));
+        directions
+    }
+
+    /// Returns all the occurrences of the word in all the directions, ordered by their starts.
+    /// A single letter word is only found once per cell.
+    pub fn find_word(&self, word: &[T]) -> Vec<NdWordMatch>
+    where
+        T: PartialEq,
+    {
+        let starts = (0..self.cells.len()).map(|idx| self.point(idx));
+        self.find_word_from(word, starts, &self.directions())
+            .into_iter()
+            .map(|(start, direction)| NdWordMatch { start, direction })
+            .collect()
+    }
+
+    /// Returns the number of occurrences of the word in all the directions
+    pub fn count_word(&self, word: &[T]) -> usize
+    where
+        T: PartialEq,
+    {
+        self.find_word(word).len()
+    }
+}
+
+impl<T: PartialEq> WordSpace for NdGrid<T> {
+    type Cell = T;
+    type Point = Vec<usize>;
+    type Direction = Vec<isize>;
+
+    fn cell(&self, point: &Vec<usize>) -> Option<&T> {
+        self.get(point)
+    }
+
+    /// Point that is `distance` steps away in the direction, if it is inside the grid
+    fn step(&self, start: &Vec<usize>, direction: &Vec<isize>, distance: usize) -> Option<Vec<usize>> {
+        start.iter().zip(direction).zip(&self.shape)
+            .map(|((&coordinate, &step), &len_axis)| {
+                let coordinate = coordinate.checked_add_signed(step.checked_mul(distance as isize)?)?;
+                (coordinate < len_axis).then_some(coordinate)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(word: &str) -> Vec<char> {
+        word.chars().collect()
+    }
+
+    #[test]
+    fn should_parse_layers() {
+        let cube = NdGrid::from_layers_with("
+            ab
+            cd
+
+            ef
+            gh
+
+            ij
+            kl
+        ", |c| c).unwrap();
+        assert_eq!(cube.shape(), &[3, 2, 2]);
+        assert_eq!(cube.get(&[1, 1, 0]), Some(&'g'));
+        assert_eq!(cube.get(&[3, 0, 0]), None);
+        assert_eq!(cube.get(&[0, 0]), None);
+        assert_eq!(cube.directions().len(), 26);
+
+        let hypercube = NdGrid::from_layers_with("a\n\nb\n\n\nc\n\nd\n\n\ne\n\nf", |c| c).unwrap();
+        assert_eq!(hypercube.shape(), &[3, 2, 1, 1]);
+        assert_eq!(hypercube.directions().len(), 80);
+
+        assert!(NdGrid::from_layers_with("ab\n\nc", |c| c).is_err());
+        assert!(NdGrid::from_layers_with("a\n\nb\n\n\nc", |c| c).is_err());
+        assert!(NdGrid::from_layers_with("", |c| c).unwrap().is_empty());
+        assert!(NdGrid::new(vec![2, 2], vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn should_find_words_through_layers() {
+        let cube = NdGrid::from_layers_with("
+            X.
+            ..
+
+            M.
+            .M
+
+            A.
+            ..
+
+            S.
+            .S
+        ", |c| c).unwrap();
+        assert_eq!(cube.find_word(&chars("XMAS")), vec![
+            NdWordMatch { start: vec![0, 0, 0], direction: vec![1, 0, 0] },
+        ]);
+        assert_eq!(cube.count_word(&chars("SAM")), 1);
+        assert_eq!(cube.count_word(&chars("MAM")), 0);
+        assert_eq!(cube.count_word(&chars("SM")), 0);
+        // Straight through the layers and diagonally from the second 'M'
+        assert_eq!(cube.count_word(&chars("MA")), 2);
+        assert_eq!(cube.count_word(&chars("S")), 2);
+        assert_eq!(cube.count_word(&[]), 0);
+    }
+}
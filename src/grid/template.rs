@@ -0,0 +1,179 @@
+use std::ops::Range;
+use super::{Addressing, Grid, Point, Transform};
+use crate::Result;
+
+
+/// Small 2D pattern that is searched in a grid, where wildcard cells match anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template<T> {
+    /// `None` is a wildcard
+    cells: Grid<Option<T>>,
+}
+
+/// Occurrence of a template in a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateMatch {
+    /// Point of the upper left cell of the template in the grid
+    pub point: Point,
+    /// Index into [`Template::orientations`] of the orientation that matched
+    pub orientation: usize,
+}
+
+impl<T: Clone + PartialEq> Template<T> {
+    const WILDCARD: char = '.';
+    const ROW_SEPARATOR: char = '/';
+
+    /// Parses a template where rows are separated by '/' and '.' is a wildcard,
+    /// e.g. "M.S/.A./M.S". Every other char is mapped to a cell.
+    pub fn parse_with(pattern: &str, mut to_cell: impl FnMut(char) -> T) -> Result<Self> {
+        let rows = pattern.replace(Self::ROW_SEPARATOR, "\n");
+        let cells = Grid::from_str_with(&rows, |c| (c != Self::WILDCARD).then(|| to_cell(c)))?;
+        Ok(Self { cells })
+    }
+
+    /// All distinct rotations by 90 degrees and their reflections, starting with the template itself
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations: Vec<Self> = Vec::with_capacity(Transform::ALL.len());
+        for transform in Transform::ALL {
+            let orientation = Self { cells: self.cells.transformed(transform) };
+            if !orientations.contains(&orientation) {
+                orientations.push(orientation);
+            }
+        }
+        orientations
+    }
+
+    /// Whether the template matches with its upper left cell at the point, as it is without rotating.
+    /// With toroidal addressing the template may wrap around the edges of the grid.
+    pub fn is_match_at(&self, grid: &Grid<T>, point: Point, addressing: Addressing) -> bool {
+        self.cells.iter().all(|(offset, cell)| {
+            let Some(expected) = cell else {
+                return true; // wildcard
+            };
+            // Offsets of a template that fits into memory also fit into isize
+            grid.step(point, offset.row as isize, offset.col as isize, addressing)
+                .is_some_and(|point| &grid[point] == expected)
+        })
+    }
+
+    /// Returns all the occurrences of the template in any of its orientations. Orientations
+    /// that match at the same point are distinct occurrences unless they cover the same cells.
+    pub fn find(&self, grid: &Grid<T>, addressing: Addressing) -> Vec<TemplateMatch> {
+        Self::find_in_rows(&self.orientations(), grid, addressing, 0..grid.nrows())
+    }
+
+    /// Same as [`Template::find`] with the rows split into bands that are searched on up to `threads` threads
+    pub fn find_parallel(&self, grid: &Grid<T>, addressing: Addressing, threads: usize) -> Vec<TemplateMatch>
+    where
+        T: Sync,
+    {
+        let orientations = self.orientations();
+        grid.search_row_bands(threads, |rows| Self::find_in_rows(&orientations, grid, addressing, rows))
+    }
+
+    /// Sorted points of the non-wildcard cells if the template matches at the point as it is
+    fn covered_points(&self, grid: &Grid<T>, point: Point, addressing: Addressing) -> Option<Vec<Point>> {
+        let mut covered = Vec::new();
+        for (offset, cell) in self.cells.iter() {
+            let Some(expected) = cell else {
+                continue; // wildcard
+            };
+            let point = grid.step(point, offset.row as isize, offset.col as isize, addressing)
+                .filter(|&point| &grid[point] == expected)?;
+            covered.push(point);
+        }
+        covered.sort_unstable();
+        Some(covered)
+    }
+
+    /// Occurrences whose upper left cell is in one of the rows
+    fn find_in_rows(orientations: &[Self], grid: &Grid<T>, addressing: Addressing, rows: Range<usize>) -> Vec<TemplateMatch> {
+        grid.points_in_rows(rows)
+            .flat_map(|point| {
+                // Orientations that cover the same cells, e.g. wrapped around a small grid, are one occurrence
+                let mut covered_by_matches: Vec<Vec<Point>> = Vec::new();
+                orientations.iter().enumerate()
+                    .filter_map(|(idx_orientation, orientation)| {
+                        let covered = orientation.covered_points(grid, point, addressing)?;
+                        if covered_by_matches.contains(&covered) {
+                            return None;
+                        }
+                        covered_by_matches.push(covered);
+                        Some(TemplateMatch { point, orientation: idx_orientation })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Returns the number of occurrences of the template in any of its orientations
+    pub fn count(&self, grid: &Grid<T>, addressing: Addressing) -> usize {
+        self.find(grid, addressing).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(pattern: &str) -> Template<char> {
+        Template::parse_with(pattern, |c| c).unwrap()
+    }
+
+    #[test]
+    fn should_generate_distinct_orientations() {
+        assert_eq!(template("M.S/.A./M.S").orientations().len(), 4);
+        assert_eq!(template("XMAS").orientations().len(), 4);
+        assert_eq!(template("AB/C.").orientations().len(), 8);
+        assert_eq!(template("A.A/.A./A.A").orientations().len(), 1);
+        assert_eq!(template("AB/C.").orientations()[0], template("AB/C."));
+    }
+
+    #[test]
+    fn should_find_rotated_and_reflected_templates() {
+        let grid: Grid<char> = "
+            S.M...
+            .A..AB
+            S.M..C
+        ".parse().unwrap();
+        let cross = template("M.S/.A./M.S");
+        let matches = cross.find(&grid, Addressing::Bounded);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].point, Point::new(0, 0));
+        assert_eq!(cross.orientations()[matches[0].orientation], template("S.M/.A./S.M"));
+
+        // Placed as "AB/.C", which is rotated and reflected
+        let matches = template("C./BA").find(&grid, Addressing::Bounded);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].point, Point::new(1, 4));
+
+        assert!(Template::parse_with("AB/C", |c| c).is_err());
+
+        // Only wrapped around the edges
+        let grid: Grid<char> = "
+            A.S
+            ...
+            ...
+        ".parse().unwrap();
+        assert_eq!(template("SA").count(&grid, Addressing::Bounded), 0);
+        assert_eq!(template("SA").find(&grid, Addressing::Toroidal), vec![
+            TemplateMatch { point: Point::new(0, 2), orientation: 0 },
+        ]);
+    }
+
+    #[test]
+    fn should_find_every_orientation_of_a_non_square_template_at_a_point() {
+        let grid: Grid<char> = "
+            XMAS
+            M...
+            A...
+            S...
+        ".parse().unwrap();
+        let xmas = template("XMAS");
+        assert_eq!(xmas.find(&grid, Addressing::Bounded), vec![
+            TemplateMatch { point: Point::new(0, 0), orientation: 0 },
+            TemplateMatch { point: Point::new(0, 0), orientation: 1 },
+        ]);
+        assert_eq!(template("SAMX").count(&grid, Addressing::Bounded), 2);
+    }
+}
@@ -0,0 +1,179 @@
+use std::ops::Index;
+use super::{Grid, Point, Window};
+
+
+/// One of the eight ways to rotate and mirror a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    Identity,
+    /// By 90 degrees clockwise
+    Rotate90,
+    Rotate180,
+    /// By 270 degrees clockwise, i.e. 90 degrees counterclockwise
+    Rotate270,
+    /// Mirrored left to right
+    FlipHorizontal,
+    /// Mirrored top to bottom
+    FlipVertical,
+    /// Mirrored along the diagonal from the upper left to the bottom right
+    Transpose,
+    /// Mirrored along the diagonal from the upper right to the bottom left
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity, Transform::Rotate90, Transform::Rotate180, Transform::Rotate270,
+        Transform::FlipHorizontal, Transform::FlipVertical, Transform::Transpose, Transform::AntiTranspose,
+    ];
+
+    /// Whether the numbers of rows and columns are swapped
+    fn swaps_axes(self) -> bool {
+        matches!(self, Transform::Rotate90 | Transform::Rotate270 | Transform::Transpose | Transform::AntiTranspose)
+    }
+}
+
+/// Transformed window of a grid that borrows the cells instead of copying them,
+/// every access maps the point back to the original grid.
+#[derive(Debug)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    /// Region of the grid that is seen, the whole grid unless it is windowed
+    window: Window,
+    transform: Transform,
+}
+
+// Derived impls would require `T: Clone` although only the reference is copied
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    /// The window has to fit into the grid
+    pub(super) fn new(grid: &'a Grid<T>, window: Window, transform: Transform) -> Self {
+        Self { grid, window, transform }
+    }
+
+    pub fn nrows(&self) -> usize {
+        if self.transform.swaps_axes() { self.window.ncols } else { self.window.nrows }
+    }
+
+    pub fn ncols(&self) -> usize {
+        if self.transform.swaps_axes() { self.window.nrows } else { self.window.ncols }
+    }
+
+    /// The same window seen with another transform
+    pub fn transformed(self, transform: Transform) -> Self {
+        Self { transform, ..self }
+    }
+
+    /// Point in the original grid of a point inside the view
+    fn source(&self, point: Point) -> Point {
+        let Point { row, col } = self.source_in_window(point);
+        Point::new(self.window.top_left.row + row, self.window.top_left.col + col)
+    }
+
+    /// Point relative to the upper left of the window of a point inside the view
+    fn source_in_window(&self, Point { row, col }: Point) -> Point {
+        let (nrows, ncols) = (self.window.nrows, self.window.ncols);
+        match self.transform {
+            Transform::Identity => Point::new(row, col),
+            Transform::Rotate90 => Point::new(nrows - 1 - col, row),
+            Transform::Rotate180 => Point::new(nrows - 1 - row, ncols - 1 - col),
+            Transform::Rotate270 => Point::new(col, ncols - 1 - row),
+            Transform::FlipHorizontal => Point::new(row, ncols - 1 - col),
+            Transform::FlipVertical => Point::new(nrows - 1 - row, col),
+            Transform::Transpose => Point::new(col, row),
+            Transform::AntiTranspose => Point::new(nrows - 1 - col, ncols - 1 - row),
+        }
+    }
+
+    pub fn get(&self, point: impl Into<Point>) -> Option<&'a T> {
+        let point = point.into();
+        if point.row >= self.nrows() || point.col >= self.ncols() {
+            return None;
+        }
+        self.grid.get(self.source(point))
+    }
+
+    /// Iterates the rows of the view from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T>> + 'a {
+        let view = *self;
+        (0..view.nrows()).map(move |row| {
+            (0..view.ncols()).map(move |col| view.get((row, col)).expect("inside the view"))
+        })
+    }
+
+    /// Owned copy of the transformed window
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            nrows: self.nrows(),
+            ncols: self.ncols(),
+            cells: self.rows().flatten().cloned().collect(),
+        }
+    }
+}
+
+impl<T, P: Into<Point>> Index<P> for GridView<'_, T> {
+    type Output = T;
+
+    fn index(&self, point: P) -> &T {
+        let point = point.into();
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is out of bounds of the {}x{} view", self.nrows(), self.ncols()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_view_all_transforms() {
+        let grid: Grid<char> = "
+            abc
+            def
+        ".parse().unwrap();
+        let transformed = |transform| grid.view(transform).to_grid().to_string();
+        assert_eq!(transformed(Transform::Identity), "abc\ndef\n");
+        assert_eq!(transformed(Transform::Rotate90), "da\neb\nfc\n");
+        assert_eq!(transformed(Transform::Rotate180), "fed\ncba\n");
+        assert_eq!(transformed(Transform::Rotate270), "cf\nbe\nad\n");
+        assert_eq!(transformed(Transform::FlipHorizontal), "cba\nfed\n");
+        assert_eq!(transformed(Transform::FlipVertical), "def\nabc\n");
+        assert_eq!(transformed(Transform::Transpose), "ad\nbe\ncf\n");
+        assert_eq!(transformed(Transform::AntiTranspose), "fc\neb\nda\n");
+
+        let view = grid.view(Transform::Rotate90);
+        assert_eq!((view.nrows(), view.ncols()), (3, 2));
+        assert_eq!(view[(2, 0)], 'f');
+        assert_eq!(view.get(Point::new(0, 2)), None);
+        assert_eq!(grid.rotated_clockwise().rotated_clockwise().rotated_clockwise().rotated_clockwise(), grid);
+        assert!(Grid::<char>::filled(0, 0, ' ').view(Transform::AntiTranspose).to_grid().is_empty());
+    }
+
+    #[test]
+    fn should_view_windows_without_copying() {
+        let grid: Grid<char> = "
+            abcd
+            efgh
+            ijkl
+        ".parse().unwrap();
+        let view = grid.view_window("1,1,2,3".parse().unwrap()).unwrap();
+        assert_eq!((view.nrows(), view.ncols()), (2, 3));
+        assert_eq!(view[(0, 0)], 'f');
+        assert!(std::ptr::eq(&view[(1, 2)], &grid[(2, 3)]));
+        assert_eq!(view.get(Point::new(2, 0)), None);
+        assert_eq!(view.to_grid().to_string(), "fgh\njkl\n");
+        assert_eq!(view.transformed(Transform::Rotate90).to_grid().to_string(), "jf\nkg\nlh\n");
+        assert_eq!(view.transformed(Transform::AntiTranspose).to_grid().to_string(), "lh\nkg\njf\n");
+        assert!(grid.view_window("2,1,2,1".parse().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+/// Cells in which words are read along straight lines of points, e.g. a 2D grid
+/// with its eight directions or an N-D grid with its 3^N - 1 directions.
+///
+/// Only the addressing of the cells differs between the dimensions,
+/// the search itself is implemented once by the provided methods.
+pub trait WordSpace {
+    type Cell: PartialEq;
+    type Point: Clone;
+    type Direction: Clone;
+
+    /// Cell at the point, `None` outside of the space
+    fn cell(&self, point: &Self::Point) -> Option<&Self::Cell>;
+
+    /// Point that is `distance` steps away from the start in the direction, `None` outside of the space
+    fn step(&self, start: &Self::Point, direction: &Self::Direction, distance: usize) -> Option<Self::Point>;
+
+    /// Whether the letters are spelled from the start in the direction
+    fn is_word_at(&self, letters: &[Self::Cell], start: &Self::Point, direction: &Self::Direction) -> bool {
+        letters.iter().enumerate().all(|(distance, letter)| {
+            self.step(start, direction, distance)
+                .is_some_and(|point| self.cell(&point) == Some(letter))
+        })
+    }
+
+    /// Returns the start and direction of every occurrence of the letters, ordered by the starts
+    /// and then by the directions. A single letter word is only found once per start, in the first direction.
+    fn find_word_from(
+        &self,
+        letters: &[Self::Cell],
+        starts: impl Iterator<Item = Self::Point>,
+        directions: &[Self::Direction],
+    ) -> Vec<(Self::Point, Self::Direction)> {
+        let directions = match letters.len() {
+            0 => &[],
+            1 => &directions[..directions.len().min(1)],
+            _ => directions,
+        };
+
+        let mut matches = Vec::new();
+        for start in starts {
+            for direction in directions {
+                if self.is_word_at(letters, &start, direction) {
+                    matches.push((start.clone(), direction.clone()));
+                }
+            }
+        }
+        matches
+    }
+}
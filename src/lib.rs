@@ -0,0 +1,12 @@
+pub mod day1_historian_hysteria;
+pub mod day2_red_nosed_reports;
+pub mod day3_mull_it_over;
+pub mod day4_ceres_search;
+
+pub mod grid;
+pub mod synthetic;
+mod error;
+
+pub use error::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,20 +1,28 @@
-mod day1_historian_hysteria;
-mod day2_red_nosed_reports;
-mod day3_mull_it_over;
-mod day4_ceres_search;
+mod cli;
 
-mod error;
-
-use day1_historian_hysteria::total_distance;
-use day2_red_nosed_reports::get_safe_reports_len;
-use day3_mull_it_over::{get_sum_of_mults, get_sum_of_mults_regex, get_sum_of_mults_with_instructions};
-use day4_ceres_search::{xmas_search, cross_search};
-use error::Error;
-
-type Result<T> = std::result::Result<T, Error>;
+use aoc_2024::day1_historian_hysteria::total_distance;
+use aoc_2024::day2_red_nosed_reports::get_safe_reports_len;
+use aoc_2024::day3_mull_it_over::{
+    ParseMode, read_input_file, open_input_file, get_sum_of_mults, get_sum_of_mults_regex,
+    get_sum_of_mults_with_instructions, get_sum_of_mults_with_instructions_regex,
+    get_sum_of_mults_with_machine, get_sum_of_mults_streaming, get_sum_of_mults_scanner,
+    get_sum_of_nested_expressions, get_trace, get_disassembly,
+};
+use aoc_2024::day4_ceres_search::{
+    SearchArea, xmas_search, cross_search, get_matches, get_rendering, get_dictionary_matches, get_path_matches,
+    xmas_search_layers,
+};
+use aoc_2024::grid::Addressing;
+use aoc_2024::synthetic::SyntheticInput;
+use aoc_2024::{Error, Result};
+use cli::{GenOptions, Options};
 
 
 fn main() -> Result<()> {
+    let options = Options::from_args()?;
+    if let Some(gen) = options.gen {
+        return generate(&gen);
+    }
 
     // DAY 1
     let total_distance = total_distance()?;
@@ -29,20 +37,122 @@ fn main() -> Result<()> {
     println!("part 2: {num_safe_reports_with_tolerance}");
 
     // DAY 3
-    let sum_of_multiplications = get_sum_of_mults()?;
-    let sum_of_muls_using_regex = get_sum_of_mults_regex()?;
-    assert_eq!(sum_of_multiplications, sum_of_muls_using_regex);
-    let sum_mults_with_instructions = get_sum_of_mults_with_instructions()?;
+    let memory = read_input_file()?;
+    let parse_mode = if options.lenient { ParseMode::Lenient } else { ParseMode::Strict };
+    let sum_of_multiplications = get_sum_of_mults(&memory, parse_mode)?;
+    let sum_of_muls_using_regex = get_sum_of_mults_regex(&memory, parse_mode)?;
+    let sum_mults_with_instructions = get_sum_of_mults_with_instructions(&memory, parse_mode)?;
+    let sum_mults_with_instructions_regex = get_sum_of_mults_with_instructions_regex(&memory, parse_mode)?;
+    let sum_of_muls_scanner = get_sum_of_mults_scanner(&memory, parse_mode, false)?;
+    let sum_mults_with_instructions_scanner = get_sum_of_mults_scanner(&memory, parse_mode, true)?;
+    // The machine, the streaming parser and the nested evaluator only parse strictly
+    let is_strict = parse_mode == ParseMode::Strict;
+    let sum_mults_with_machine = is_strict.then(|| get_sum_of_mults_with_machine(&memory)).transpose()?;
+    let sum_of_muls_streaming = is_strict.then(|| get_sum_of_mults_streaming(open_input_file()?, false)).transpose()?;
+    let sum_mults_with_instructions_streaming = is_strict.then(|| get_sum_of_mults_streaming(open_input_file()?, true)).transpose()?;
+    let sum_of_nested_expressions = is_strict.then(|| get_sum_of_nested_expressions(&memory)).transpose()?;
+    let mut cross_checks = CrossChecks::default();
     println!("-- Day 03: Sum of parsed mult. instruction results --");
-    println!("part 1: {sum_of_multiplications}");
-    println!("part 2: {sum_mults_with_instructions}");
+    println!("part 1: {sum_of_multiplications} (regex: {}, stream: {}, scanner: {})",
+        cross_checks.check("part 1 regex", sum_of_multiplications, sum_of_muls_using_regex),
+        cross_checks.check_strict("part 1 stream", sum_of_multiplications, sum_of_muls_streaming),
+        cross_checks.check("part 1 scanner", sum_of_multiplications, sum_of_muls_scanner));
+    println!("part 2: {sum_mults_with_instructions} (regex: {}, machine: {}, stream: {}, scanner: {}, nested: {})",
+        cross_checks.check("part 2 regex", sum_mults_with_instructions, sum_mults_with_instructions_regex),
+        cross_checks.check_strict("part 2 machine", sum_mults_with_instructions, sum_mults_with_machine),
+        cross_checks.check_strict("part 2 stream", sum_mults_with_instructions, sum_mults_with_instructions_streaming),
+        cross_checks.check("part 2 scanner", sum_mults_with_instructions, sum_mults_with_instructions_scanner),
+        cross_checks.check_strict("part 2 nested", sum_mults_with_instructions, sum_of_nested_expressions));
+    if options.trace {
+        println!("-- Day 03: Trace of part 2 --");
+        print!("{}", get_trace(&memory, parse_mode)?);
+    }
+    if options.disassemble {
+        println!("-- Day 03: Disassembly --");
+        print!("{}", get_disassembly(&memory, parse_mode));
+    }
 
     // DAY 4
-    let num_xmas = xmas_search()?;
-    let num_cross = cross_search()?;
+    let area = SearchArea {
+        window: options.window,
+        addressing: if options.torus { Addressing::Toroidal } else { Addressing::Bounded },
+    };
+    let num_xmas = xmas_search(area, options.threads)?;
+    let num_cross = cross_search(area, options.threads)?;
     println!("-- Day 04: Num. of 'XMAS' occurances");
     println!("part 1: {num_xmas}");
     println!("part 2: {num_cross}");
+    if let Some(format) = options.matches {
+        println!("-- Day 04: Matches --");
+        print!("{}", get_matches(area, format)?);
+    }
+    if let Some(colouring) = options.render {
+        println!("-- Day 04: Rendering --");
+        print!("{}", get_rendering(area, colouring)?);
+    }
+    if let Some(words) = options.words {
+        println!("-- Day 04: Dictionary search --");
+        print!("{}", get_dictionary_matches(area, &words, options.ignore_case)?);
+    }
+    if let Some(words) = options.paths {
+        println!("-- Day 04: Path search --");
+        print!("{}", get_path_matches(area, &words, options.max_path_len, options.ignore_case)?);
+    }
+    if let Some(path) = options.layers {
+        println!("-- Day 04: Num. of 'XMAS' occurances in {} --", path.display());
+        println!("{}", xmas_search_layers(&path)?);
+    }
 
+    // Fails after all the days are printed, so a mismatch does not hide the other answers
+    cross_checks.into_result()
+}
+
+/// Generates a puzzle input and prints it or writes it to the output file
+fn generate(gen: &GenOptions) -> Result<()> {
+    let synthetic = SyntheticInput::generate(&gen.spec)?;
+    match &gen.output {
+        Some(path) => synthetic.write(path, gen.expected)?,
+        None => {
+            print!("{}", synthetic.input);
+            // Not mixed into the printed input, which can be piped into a file
+            if gen.expected {
+                eprint!("{}", synthetic.answers_to_string());
+            }
+        },
+    }
     Ok(())
 }
+
+/// Answers compared with the ones of alternative implementations
+#[derive(Default)]
+struct CrossChecks {
+    /// e.g. "part 1 regex: 41 instead of 42"
+    mismatches: Vec<String>,
+}
+
+impl CrossChecks {
+    /// Compares an answer with the one of an alternative implementation
+    fn check(&mut self, name: &str, answer: u64, alternative: u64) -> String {
+        if answer == alternative {
+            "ok".to_string()
+        } else {
+            self.mismatches.push(format!("{name}: {alternative} instead of {answer}"));
+            format!("MISMATCH {alternative}")
+        }
+    }
+
+    /// Compares an answer with the one of an alternative implementation that
+    /// only supports the strict parse mode and is not run otherwise
+    fn check_strict(&mut self, name: &str, answer: u64, alternative: Option<u64>) -> String {
+        alternative.map_or_else(|| "skipped".to_string(), |alternative| self.check(name, answer, alternative))
+    }
+
+    /// Fails if any of the alternatives disagreed
+    fn into_result(self) -> Result<()> {
+        if self.mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::CrossCheckMismatch { msg: self.mismatches.join(", ") })
+        }
+    }
+}
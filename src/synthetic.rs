@@ -0,0 +1,111 @@
+use std::path::Path;
+use crate::day1_historian_hysteria::generate_location_lists;
+use crate::day2_red_nosed_reports::generate_reports;
+use crate::day3_mull_it_over::{generate_memory, DEFAULT_TOGGLE_DENSITY};
+use crate::day4_ceres_search::{generate_word_search, DEFAULT_XMAS_DENSITY};
+use crate::{Error, Result};
+
+
+/// What a generated puzzle input looks like
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyntheticSpec {
+    pub day: u32,
+    /// Lines of the lists of day 1, reports of day 2, planted muls of day 3
+    /// and rows and columns of the word search of day 4
+    pub size: usize,
+    /// The same seed always generates the same input
+    pub seed: u64,
+    /// Day 3: probability that a mul is preceded by 'do()' or "don't()",
+    /// day 4: share of the cells that a planted "XMAS" starts at.
+    /// `None` picks the default of the day.
+    pub density: Option<f64>,
+}
+
+
+impl Default for SyntheticSpec {
+    fn default() -> Self {
+        Self { day: 1, size: 1000, seed: 0, density: None }
+    }
+}
+
+/// A generated puzzle input with the answers of its parts, day 1 only has one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntheticInput {
+    pub input: String,
+    pub answers: Vec<u64>,
+}
+
+impl SyntheticInput {
+    /// Generates the input of the day with answers of reference solvers
+    pub fn generate(spec: &SyntheticSpec) -> Result<Self> {
+        if let Some(density) = spec.density.filter(|density| !(0.0..=1.0).contains(density)) {
+            return Err(Error::CannotGeneratePuzzle { msg: format!("the density {density} is not between 0 and 1") });
+        }
+        if spec.density.is_some() && !matches!(spec.day, 3 | 4) {
+            return Err(Error::CannotGeneratePuzzle { msg: format!("day {} has no density", spec.day) });
+        }
+
+        match spec.day {
+            1 => Ok(generate_location_lists(spec.size, spec.seed)),
+            2 => Ok(generate_reports(spec.size, spec.seed)),
+            3 => Ok(generate_memory(spec.size, spec.density.unwrap_or(DEFAULT_TOGGLE_DENSITY), spec.seed)),
+            4 => generate_word_search(spec.size, spec.density.unwrap_or(DEFAULT_XMAS_DENSITY), spec.seed),
+            day => Err(Error::CannotGeneratePuzzle { msg: format!("day {day} is not implemented") }),
+        }
+    }
+
+    /// Answers one per line, e.g. "part 1: 11"
+    pub fn answers_to_string(&self) -> String {
+        self.answers.iter()
+            .enumerate()
+            .map(|(idx_part, answer)| format!("part {}: {answer}\n", idx_part + 1))
+            .collect()
+    }
+
+    /// Writes the input to the path and, if `with_answers` is set, the answers next to it,
+    /// e.g. to 'input.txt.expected'
+    pub fn write(&self, path: &Path, with_answers: bool) -> Result<()> {
+        let write = |path: &Path, contents: &str| {
+            std::fs::write(path, contents)
+                .map_err(|e| Error::CannotWriteFile { msg: format!("{}: {e}", path.display()) })
+        };
+        write(path, &self.input)?;
+        if with_answers {
+            let mut path_expected = path.as_os_str().to_owned();
+            path_expected.push(".expected");
+            write(Path::new(&path_expected), &self.answers_to_string())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_generate_every_day_deterministically() {
+        for day in 1..=4 {
+            let spec = SyntheticSpec { day, size: 20, seed: 3, ..SyntheticSpec::default() };
+            let input = SyntheticInput::generate(&spec).unwrap();
+            assert!(!input.input.is_empty(), "day {day}");
+            assert_eq!(input.answers.len(), if day == 1 { 1 } else { 2 }, "day {day}");
+            assert_eq!(SyntheticInput::generate(&spec).unwrap(), input, "day {day}");
+            assert_ne!(SyntheticInput::generate(&SyntheticSpec { seed: 4, ..spec }).unwrap(), input, "day {day}");
+        }
+    }
+
+    #[test]
+    fn should_reject_unknown_days_and_densities() {
+        assert!(SyntheticInput::generate(&SyntheticSpec { day: 5, ..SyntheticSpec::default() }).is_err());
+        assert!(SyntheticInput::generate(&SyntheticSpec { day: 3, density: Some(1.5), ..SyntheticSpec::default() }).is_err());
+        assert!(SyntheticInput::generate(&SyntheticSpec { day: 4, density: Some(f64::NAN), ..SyntheticSpec::default() }).is_err());
+        assert!(SyntheticInput::generate(&SyntheticSpec { day: 1, density: Some(0.5), ..SyntheticSpec::default() }).is_err());
+    }
+
+    #[test]
+    fn should_format_answers() {
+        let input = SyntheticInput { input: String::new(), answers: vec![11, 31] };
+        assert_eq!(input.answers_to_string(), "part 1: 11\npart 2: 31\n");
+    }
+}